@@ -1,26 +1,83 @@
-use crate::{types::{WoojinValue, WoojinValueKind}, variable::VariableOption, calc::Calc};
+use std::sync::Arc;
+
+use crate::{types::{WoojinValue, WoojinValueKind}, variable::VariableOption, calc::Expr, error::Span};
+
+/// A syntax tree node and where it was written.
+#[derive(Debug, Clone)]
+pub(crate) struct Spanned<T> {
+  pub node: T,
+  pub span: Span,
+}
+
+pub(crate) type Stmt = Spanned<Statements>;
 
 #[derive(Debug, Clone)]
 pub(crate) enum Statements {
+  #[allow(dead_code)]
   Comment(String),
-  Calc(Calc),
-  Print { values: Vec<Box<Statements>> },
-  Println { values: Vec<Box<Statements>> },
-  Assignment { name: String, value: Box<Statements> },
-  Input { question: Box<Statements> },
+  Calc(Expr),
+  Print { values: Vec<Stmt> },
+  Println { values: Vec<Stmt> },
+  Assignment { name: String, value: Box<Stmt> },
+  IndexAssignment { name: String, indices: Vec<Expr>, value: Box<Stmt> },
+  Input { question: Box<Stmt> },
+  /// `setenv <name>, <value>`, only allowed when the interpreter permits it.
+  SetEnv { name: Box<Stmt>, value: Box<Stmt> },
   Let {
     name: String,
     kind: WoojinValueKind,
-    stmt: Box<Statements>,
+    stmt: Box<Stmt>,
     option: VariableOption,
   },
   If {
-    condition: Box<Statements>,
-    stmt: Vec<Box<Statements>>,
-    else_stmt: Vec<Box<Statements>>,
+    condition: Box<Stmt>,
+    stmt: Vec<Stmt>,
+    else_stmt: Vec<Stmt>,
+  },
+  While {
+    condition: Box<Stmt>,
+    body: Vec<Stmt>,
+  },
+  For {
+    name: String,
+    iter: Iterable,
+    body: Vec<Stmt>,
   },
-  Roar { value: WoojinValue },
+  Function {
+    name: String,
+    params: Vec<(String, WoojinValueKind)>,
+    body: Vec<Stmt>,
+  },
+  /// `try:` and its `catch $name:` handler. The name is optional.
+  Try {
+    body: Vec<Stmt>,
+    name: Option<String>,
+    handler: Vec<Stmt>,
+  },
+  Return { value: Option<Box<Stmt>> },
+  Break,
+  Continue,
+  /// `roar <message>` or `roar <message>, <exit code>`.
+  Roar { value: Box<Stmt>, code: Option<Box<Stmt>> },
   Yee { code: i32 },
   Value { value: WoojinValue },
-  Sleep { value: Box<Statements> }
-}
\ No newline at end of file
+  Sleep { value: Box<Stmt> }
+}
+
+/// What a `for` loop walks over.
+#[derive(Debug, Clone)]
+pub(crate) enum Iterable {
+  /// `start..end`, end exclusive.
+  Range { start: Box<Stmt>, end: Box<Stmt> },
+  /// Any value that evaluates to an array.
+  Value(Box<Stmt>),
+}
+
+/// A user-defined function, registered when its `fn` statement runs.
+#[derive(Debug, Clone)]
+pub(crate) struct Function {
+  pub params: Vec<(String, WoojinValueKind)>,
+  pub body: Vec<Stmt>,
+  /// The source code the function was written in, quoted by the reports of errors inside it.
+  pub source: Arc<str>,
+}
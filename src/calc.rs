@@ -1,123 +1,304 @@
-use nom::{
-  IResult,
-  branch::{alt},
-  multi::{fold_many0},
-  bytes::complete::{ tag },
-  character::complete::{ char, space0 },
-  sequence::{pair, delimited},
-  combinator::{map}
-};
-use crate::{types::{WoojinValue, parse::parse_value}, parser::{WoojinResult}, error::{WoojinError}};
+use crate::{types::{WoojinValue, WoojinValueKind, parse::parse_number}, parser::{WoojinResult, Parser}, error::{WoojinError, WoojinErrorKind, Span}, ast::Spanned, lexer::{Keyword, TokenKind}};
 
 #[derive(Debug, Clone)]
 pub(crate) enum Calc {
   Value(WoojinValue),
-  Add(Box<Calc>, Box<Calc>),
-  Sub(Box<Calc>, Box<Calc>),
-  Mul(Box<Calc>, Box<Calc>),
-  Div(Box<Calc>, Box<Calc>),
-  Equal(Box<Calc>, Box<Calc>),
-  NotEqual(Box<Calc>, Box<Calc>),
-  GreaterThan(Box<Calc>, Box<Calc>),
-  LessThan(Box<Calc>, Box<Calc>),
-  GreaterThanOrEqual(Box<Calc>, Box<Calc>),
-  LessThanOrEqual(Box<Calc>, Box<Calc>),
-}
-
-pub(crate) fn parse_primary(input: &str) -> IResult<&str, Calc> {
-  alt((
-      map(parse_value, |value| Calc::Value(value)),
-      delimited(
-          char('('),
-          delimited(space0, parse_expr, space0),
-          char(')'),
-      )
-  ))(input.trim())
-}
-
-pub(crate) fn parse_expr(input: &str) -> IResult<&str, Calc> {
-  let (input, init): (&str, Calc) = parse_term(input)?;
-  let (input, pairs) = fold_many0(
-      pair(alt((tag("+"), tag("-"))), parse_term),
-      move || init.clone(),
-      |acc, (op, val)| match op {
-          "+" => Calc::Add(Box::new(acc), Box::new(val)),
-          "-" => Calc::Sub(Box::new(acc), Box::new(val)),
-          _ => unreachable!(),
-      },
-  )(input.trim())?;
-  Ok((input.trim(), pairs))
-}
-
-pub(crate) fn parse_term(input: &str) -> IResult<&str, Calc> {
-  let (input, init): (&str, Calc) = parse_factor(input)?;
-  let (input, pairs) = fold_many0(
-      pair(alt((tag("*"), tag("/"))), parse_factor),
-      move || init.clone(),
-      |acc, (op, val)| match op {
-          "*" => Calc::Mul(Box::new(acc), Box::new(val)),
-          "/" => Calc::Div(Box::new(acc), Box::new(val)),
-          _ => unreachable!(),
-      },
-  )(input.trim())?;
-  Ok((input.trim(), pairs))
-}
-
-pub(crate) fn parse_factor(input: &str) -> IResult<&str, Calc> {
-  let (input, init): (&str, Calc) = parse_comparison(input)?;
-  let (input, pairs) = fold_many0(
-      pair(
-          alt((
-              tag("="),
-              tag("!="),
-          )),
-          alt((
-            parse_primary,
-          )),
-      ),
-      move || init.clone(),
-      |acc, (op, val)| match op {
-          "=" => Calc::Equal(Box::new(acc), Box::new(val)),
-          "!=" => Calc::NotEqual(Box::new(acc), Box::new(val)),
-          _ => unreachable!(),
-      },
-  )(input.trim())?;
-
-  Ok((input.trim(), pairs))
-}
-
-pub(crate) fn parse_comparison(input: &str) -> IResult<&str, Calc> {
-  let (input, init) = parse_primary(input)?;
-  let (input, pairs) = fold_many0(
-      pair(
-          alt((
-              tag("=="),
-              tag("!="),
-              tag("="),
-              tag(">="),
-              tag("<="),
-              tag("<"),
-              tag(">"),
-          )),
-          parse_primary,
-      ),
-      || init.clone(),
-      |acc, (op, val)| match op {
-          "==" => Calc::Equal(Box::new(acc), Box::new(val)),
-          "!=" => Calc::NotEqual(Box::new(acc), Box::new(val)),
-          ">=" => Calc::GreaterThanOrEqual(Box::new(acc), Box::new(val)),
-          "<=" => Calc::LessThanOrEqual(Box::new(acc), Box::new(val)),
-          "<" => Calc::LessThan(Box::new(acc), Box::new(val)),
-          ">" => Calc::GreaterThan(Box::new(acc), Box::new(val)),
-          _ => unreachable!(),
+  /// The first operand and then every operator with the operand after it, applied from left
+  /// to right: `1 - 2 + 3` is `Chain(1, [(Sub, 2), (Add, 3)])`. A chain holds operators of one
+  /// binding level only, and stays flat however long it is.
+  Chain(Box<Expr>, Vec<(BinaryOp, Expr)>),
+  Pow(Box<Expr>, Box<Expr>),
+  Call(String, Vec<Expr>),
+  Array(Vec<Expr>),
+  Index(Box<Expr>, Box<Expr>),
+  Field(Box<Expr>, String),
+  Not(Box<Expr>),
+  Neg(Box<Expr>),
+}
+
+/// The left-associative operators, which are kept in a [`Calc::Chain`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BinaryOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Mod,
+  FloorDiv,
+  Equal,
+  NotEqual,
+  GreaterThan,
+  LessThan,
+  GreaterThanOrEqual,
+  LessThanOrEqual,
+  And,
+  Or,
+}
+
+impl BinaryOp {
+  /// How the operator combines two values. `and` and `or` have none, because they
+  /// only evaluate their right operand when the left one doesn't decide the result.
+  pub(crate) fn function(self) -> Option<fn(&WoojinValue, &WoojinValue) -> WoojinResult<WoojinValue>> {
+    match self {
+      BinaryOp::Add => Some(ValueCalc::add),
+      BinaryOp::Sub => Some(ValueCalc::sub),
+      BinaryOp::Mul => Some(ValueCalc::mul),
+      BinaryOp::Div => Some(ValueCalc::div),
+      BinaryOp::Mod => Some(ValueCalc::rem),
+      BinaryOp::FloorDiv => Some(ValueCalc::floor_div),
+      BinaryOp::Equal => Some(ValueCalc::equal),
+      BinaryOp::NotEqual => Some(ValueCalc::not_equal),
+      BinaryOp::GreaterThan => Some(ValueCalc::biggerthen),
+      BinaryOp::LessThan => Some(ValueCalc::smallerthen),
+      BinaryOp::GreaterThanOrEqual => Some(ValueCalc::biggerthen_equal),
+      BinaryOp::LessThanOrEqual => Some(ValueCalc::smallerthen_equal),
+      BinaryOp::And | BinaryOp::Or => None,
+    }
+  }
+}
+
+pub(crate) type Expr = Spanned<Calc>;
+
+// Expression grammar, from the loosest to the tightest binding level:
+//
+//    1. `or` `||`                          left-associative
+//    2. `and` `&&`                         left-associative
+//    3. `not` `!`                          prefix
+//    4. `==` `=` `!=` `<` `<=` `>` `>=`    left-associative, `=` is an alias of `==`
+//    5. `+` `-`                            left-associative
+//    6. `*` `/` `//` `%`                   left-associative
+//    7. `-`                                prefix, so -2 ** 2 == -(2 ** 2)
+//    8. `**`                               right-associative, so 2 ** 3 ** 2 == 2 ** (3 ** 2)
+//    9. `[index]` `.field`                 postfix
+//   10. literals, `$variables`, calls, `[arrays]`, `(parentheses)` and `env <name>`
+//
+// Every level is one `parse_*` function that only calls the level below it
+// (or, for prefix and right-associative operators, itself). They all work on the
+// tokens of the lexer and leave the parser on the first token after the expression.
+//
+// Brackets, prefix operators and the right side of `**` make the tree one level deeper,
+// which is counted with `Parser::enter`, so that expressions can't nest deep enough to
+// overflow the stack. The left-associative levels are parsed and run in a loop instead,
+// so a long chain like `1 + 2 + ... + 1000` doesn't nest at all.
+
+/// Builds a binary node spanning both of its operands.
+fn binary(op: fn(Box<Expr>, Box<Expr>) -> Calc, left: Expr, right: Expr) -> Expr {
+  let span = left.span.to(right.span);
+  Spanned { node: op(Box::new(left), Box::new(right)), span }
+}
+
+/// Parses operands with `next` for as long as they are joined by an operator `op` recognizes.
+fn parse_chain(p: &mut Parser, next: fn(&mut Parser) -> WoojinResult<Expr>, op: fn(&TokenKind) -> Option<BinaryOp>) -> WoojinResult<Expr> {
+  let first: Expr = next(p)?;
+  let mut links: Vec<(BinaryOp, Expr)> = Vec::new();
+  while let Some(op) = op(&p.peek().kind) {
+    p.advance();
+    links.push((op, next(p)?));
+  }
+  let span: Span = match links.last() {
+    Some((_, last)) => first.span.to(last.span),
+    None => return Ok(first)
+  };
+  Ok(Spanned { node: Calc::Chain(Box::new(first), links), span })
+}
+
+pub(crate) fn parse_calc(p: &mut Parser) -> WoojinResult<Expr> {
+  parse_or(p)
+}
+
+// 1
+pub(crate) fn parse_or(p: &mut Parser) -> WoojinResult<Expr> {
+  parse_chain(p, parse_and, |kind| match kind {
+    TokenKind::Symbol("||") | TokenKind::Keyword(Keyword::Or) => Some(BinaryOp::Or),
+    _ => None,
+  })
+}
+
+// 2
+pub(crate) fn parse_and(p: &mut Parser) -> WoojinResult<Expr> {
+  parse_chain(p, parse_not, |kind| match kind {
+    TokenKind::Symbol("&&") | TokenKind::Keyword(Keyword::And) => Some(BinaryOp::And),
+    _ => None,
+  })
+}
+
+// 3
+pub(crate) fn parse_not(p: &mut Parser) -> WoojinResult<Expr> {
+  if !matches!(p.peek().kind, TokenKind::Symbol("!") | TokenKind::Keyword(Keyword::Not)) { return parse_comparison(p); }
+  let start: Span = p.advance().span;
+  p.enter()?;
+  let value: Expr = parse_not(p)?;
+  p.leave(1);
+  Ok(Spanned { span: start.to(value.span), node: Calc::Not(Box::new(value)) })
+}
+
+// 4
+pub(crate) fn parse_comparison(p: &mut Parser) -> WoojinResult<Expr> {
+  parse_chain(p, parse_sum, |kind| match kind {
+    TokenKind::Symbol("==") | TokenKind::Symbol("=") => Some(BinaryOp::Equal),
+    TokenKind::Symbol("!=") => Some(BinaryOp::NotEqual),
+    TokenKind::Symbol("<=") => Some(BinaryOp::LessThanOrEqual),
+    TokenKind::Symbol(">=") => Some(BinaryOp::GreaterThanOrEqual),
+    TokenKind::Symbol("<") => Some(BinaryOp::LessThan),
+    TokenKind::Symbol(">") => Some(BinaryOp::GreaterThan),
+    _ => None,
+  })
+}
+
+// 5
+pub(crate) fn parse_sum(p: &mut Parser) -> WoojinResult<Expr> {
+  parse_chain(p, parse_product, |kind| match kind {
+    TokenKind::Symbol("+") => Some(BinaryOp::Add),
+    TokenKind::Symbol("-") => Some(BinaryOp::Sub),
+    _ => None,
+  })
+}
+
+// 6
+pub(crate) fn parse_product(p: &mut Parser) -> WoojinResult<Expr> {
+  parse_chain(p, parse_unary, |kind| match kind {
+    TokenKind::Symbol("*") => Some(BinaryOp::Mul),
+    TokenKind::Symbol("//") => Some(BinaryOp::FloorDiv),
+    TokenKind::Symbol("/") => Some(BinaryOp::Div),
+    TokenKind::Symbol("%") => Some(BinaryOp::Mod),
+    _ => None,
+  })
+}
+
+// 7
+pub(crate) fn parse_unary(p: &mut Parser) -> WoojinResult<Expr> {
+  if p.peek().kind != TokenKind::Symbol("-") { return parse_power(p); }
+  let start: Span = p.advance().span;
+  // A negative number literal stays a literal (so the smallest int can be written),
+  // unless it is the base of `**`.
+  if let TokenKind::Number(digits) = &p.peek().kind {
+    if p.nth(1).kind != TokenKind::Symbol("**") {
+      let value: WoojinValue = number(&format!("-{}", digits), p.peek().span)?;
+      let span: Span = start.to(p.advance().span);
+      return Ok(Spanned { node: Calc::Value(value), span });
+    }
+  }
+  p.enter()?;
+  let value: Expr = parse_unary(p)?;
+  p.leave(1);
+  Ok(Spanned { span: start.to(value.span), node: Calc::Neg(Box::new(value)) })
+}
+
+// 8
+pub(crate) fn parse_power(p: &mut Parser) -> WoojinResult<Expr> {
+  let base: Expr = parse_postfix(p)?;
+  if !p.eat(&TokenKind::Symbol("**")) { return Ok(base); }
+  p.enter()?;
+  let exponent: Expr = parse_unary(p)?;
+  p.leave(1);
+  Ok(binary(Calc::Pow, base, exponent))
+}
+
+/// What can follow an expression at the postfix level.
+enum Postfix {
+  Index(Expr),
+  Field(String),
+}
+
+// 9
+pub(crate) fn parse_postfix(p: &mut Parser) -> WoojinResult<Expr> {
+  let mut value: Expr = parse_primary(p)?;
+  let mut levels: usize = 0;
+  loop {
+    if matches!(p.peek().kind, TokenKind::Symbol("[") | TokenKind::Symbol(".")) {
+      p.enter()?;
+      levels += 1;
+    }
+    let postfix: Postfix = match p.peek().kind {
+      TokenKind::Symbol("[") => Postfix::Index(parse_index(p)?),
+      TokenKind::Symbol(".") => {
+        p.advance();
+        match p.peek().kind.clone() {
+          TokenKind::Ident(name) => { p.advance(); Postfix::Field(name) },
+          _ => return Err(p.unexpected("a field name after `.`"))
+        }
       },
-  )(input.trim())?;
+      _ => { p.leave(levels); return Ok(value); },
+    };
+    let span: Span = p.span_from(value.span);
+    let node: Calc = match postfix {
+      Postfix::Index(index) => Calc::Index(Box::new(value), Box::new(index)),
+      Postfix::Field(name) => Calc::Field(Box::new(value), name),
+    };
+    value = Spanned { node, span };
+  }
+}
+
+pub(crate) fn parse_index(p: &mut Parser) -> WoojinResult<Expr> {
+  p.expect(&TokenKind::Symbol("["), "before the index")?;
+  p.enter()?;
+  let index: Expr = parse_or(p)?;
+  p.leave(1);
+  p.expect(&TokenKind::Symbol("]"), "after the index")?;
+  Ok(index)
+}
+
+// 10
+pub(crate) fn parse_primary(p: &mut Parser) -> WoojinResult<Expr> {
+  let start: Span = p.peek().span;
+  let node: Calc = match p.peek().kind.clone() {
+    TokenKind::Number(digits) => Calc::Value(number(&digits, start)?),
+    TokenKind::Str(value) => Calc::Value(WoojinValue::String(value)),
+    TokenKind::Bool(value) => Calc::Value(WoojinValue::Bool(value)),
+    TokenKind::Var(name) => Calc::Value(WoojinValue::Var(name)),
+    TokenKind::Ident(name) if p.nth(1).kind == TokenKind::Symbol("(") => {
+      p.advance();
+      p.advance();
+      Calc::Call(name, parse_list(p, ")")?)
+    },
+    TokenKind::Ident(name) => return Err(WoojinError::new(format!("Unknown word `{}`", name), WoojinErrorKind::UnknownToken)
+      .with_help(format!("variables start with a `$`, like `${}`, and functions are called with parentheses, like `{}()`", name, name))
+      .with_span(start)),
+    TokenKind::Symbol("[") => { p.advance(); Calc::Array(parse_list(p, "]")?) },
+    // `env "HOME"` is a call of the builtin `env`, whose one argument needs no parentheses.
+    TokenKind::Keyword(Keyword::Env) => {
+      p.advance();
+      p.enter()?;
+      let name: Expr = parse_postfix(p)?;
+      p.leave(1);
+      Calc::Call("env".to_string(), vec![name])
+    },
+    TokenKind::Symbol("(") => {
+      p.advance();
+      p.enter()?;
+      let value: Expr = parse_or(p)?;
+      p.leave(1);
+      p.expect(&TokenKind::Symbol(")"), "to close the `(`")?;
+      return Ok(value);
+    },
+    _ => return Err(p.unexpected("a value"))
+  };
+  if !matches!(node, Calc::Call(..) | Calc::Array(_)) { p.advance(); }
+  Ok(Spanned { node, span: p.span_from(start) })
+}
 
-  Ok((input, pairs))
+/// Comma-separated expressions up to `close`, which is consumed. The opening bracket already is.
+fn parse_list(p: &mut Parser, close: &'static str) -> WoojinResult<Vec<Expr>> {
+  let mut values: Vec<Expr> = Vec::new();
+  p.enter()?;
+  while p.peek().kind != TokenKind::Symbol(close) {
+    values.push(parse_or(p)?);
+    if !p.eat(&TokenKind::Symbol(",")) { break; }
+  }
+  p.leave(1);
+  p.expect(&TokenKind::Symbol(close), "to close the list")?;
+  Ok(values)
 }
 
-pub(crate) fn parse_calc(input: &str) -> IResult<&str, Calc> {
-  delimited(space0, parse_expr, space0)(input)
+/// The value of a number literal, which may start with a `-`.
+fn number(text: &str, span: Span) -> WoojinResult<WoojinValue> {
+  match parse_number(text) {
+    Ok(("", value)) => Ok(value),
+    _ if text.trim_start_matches('-').bytes().all(|c| c.is_ascii_digit()) => Err(WoojinError::new(format!("`{}` doesn't fit in an int", text), WoojinErrorKind::ParseError)
+      .with_help(format!("write it as a long: `{}L`", text))
+      .with_span(span)),
+    _ => Err(WoojinError::new(format!("`{}` is not a valid number", text), WoojinErrorKind::ParseError).with_span(span)),
+  }
 }
 
 pub(crate) trait ValueCalc {
@@ -131,19 +312,38 @@ pub(crate) trait ValueCalc {
   fn sub(&self, other: &WoojinValue) -> WoojinResult<WoojinValue>;
   fn mul(&self, other: &WoojinValue) -> WoojinResult<WoojinValue>;
   fn div(&self, other: &WoojinValue) -> WoojinResult<WoojinValue>;
+  fn rem(&self, other: &WoojinValue) -> WoojinResult<WoojinValue>;
+  fn pow(&self, other: &WoojinValue) -> WoojinResult<WoojinValue>;
+  fn floor_div(&self, other: &WoojinValue) -> WoojinResult<WoojinValue>;
+  fn neg(&self) -> WoojinResult<WoojinValue>;
+  fn index(&self, index: &WoojinValue) -> WoojinResult<WoojinValue>;
+  fn field(&self, name: &str) -> WoojinResult<WoojinValue>;
+}
+
+/// Widens two numbers to their common kind (see [`WoojinValueKind::common`]),
+/// so the arithmetic below only has to handle pairs of the same kind.
+pub(crate) fn promote(a: &WoojinValue, b: &WoojinValue) -> Option<(WoojinValue, WoojinValue)> {
+  let kind: WoojinValueKind = WoojinValueKind::common(a.kind(), b.kind())?;
+  Some((a.widen(kind)?, b.widen(kind)?))
 }
 
 impl ValueCalc for WoojinValue {
   fn equal(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Bool(a == b)),
-      (WoojinValue::Long(a), WoojinValue::Long(b)) => Ok(WoojinValue::Bool(a == b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Bool(a == b)),
-      (WoojinValue::Double(a), WoojinValue::Double(b)) => Ok(WoojinValue::Bool(a == b)),
+    if let Some(pair) = promote(self, other) {
+      return match pair {
+        (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Bool(a == b)),
+        (WoojinValue::Long(a), WoojinValue::Long(b)) => Ok(WoojinValue::Bool(a == b)),
+        (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Bool(a == b)),
+        (WoojinValue::Double(a), WoojinValue::Double(b)) => Ok(WoojinValue::Bool(a == b)),
+        _ => unreachable!(),
+      };
+    }
+    match (self.clone(), other.clone()) {
       (WoojinValue::String(a), WoojinValue::String(b)) => Ok(WoojinValue::Bool(a == b)),
       (WoojinValue::Bool(a), WoojinValue::Bool(b)) => Ok(WoojinValue::Bool(a == b)),
+      (WoojinValue::Array(a), WoojinValue::Array(b)) => array_equal(&a, &b),
       _ => Err(WoojinError::new("The type that can't be compared", crate::error::WoojinErrorKind::CannotCompare)),
-    }  
+    }
   }
 
   fn not_equal(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
@@ -154,74 +354,81 @@ impl ValueCalc for WoojinValue {
   }
 
   fn biggerthen(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Bool(a > b)),
-      (WoojinValue::Long(a), WoojinValue::Long(b)) => Ok(WoojinValue::Bool(a > b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Bool(a > b)),
-      (WoojinValue::Double(a), WoojinValue::Double(b)) => Ok(WoojinValue::Bool(a > b)),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => Ok(WoojinValue::Bool(a > b)),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => Ok(WoojinValue::Bool(a > b)),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => Ok(WoojinValue::Bool(a > b)),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => Ok(WoojinValue::Bool(a > b)),
       _ => Err(WoojinError::new("The type that can't be compared", crate::error::WoojinErrorKind::CannotCompare))
     }
   }
 
   fn smallerthen(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Bool(a < b)),
-      (WoojinValue::Long(a), WoojinValue::Long(b)) => Ok(WoojinValue::Bool(a < b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Bool(a < b)),
-      (WoojinValue::Double(a), WoojinValue::Double(b)) => Ok(WoojinValue::Bool(a < b)),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => Ok(WoojinValue::Bool(a < b)),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => Ok(WoojinValue::Bool(a < b)),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => Ok(WoojinValue::Bool(a < b)),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => Ok(WoojinValue::Bool(a < b)),
       _ => Err(WoojinError::new("The type that can't be compared", crate::error::WoojinErrorKind::CannotCompare))
     }
   }
 
   fn biggerthen_equal(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Bool(a >= b)),
-      (WoojinValue::Long(a), WoojinValue::Long(b)) => Ok(WoojinValue::Bool(a >= b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Bool(a >= b)),
-      (WoojinValue::Double(a), WoojinValue::Double(b)) => Ok(WoojinValue::Bool(a >= b)),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => Ok(WoojinValue::Bool(a >= b)),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => Ok(WoojinValue::Bool(a >= b)),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => Ok(WoojinValue::Bool(a >= b)),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => Ok(WoojinValue::Bool(a >= b)),
       _ => Err(WoojinError::new("The type that can't be compared", crate::error::WoojinErrorKind::CannotCompare))
     }
   }
 
   fn smallerthen_equal(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Bool(a <= b)),
-      (WoojinValue::Long(a), WoojinValue::Long(b)) => Ok(WoojinValue::Bool(a <= b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Bool(a <= b)),
-      (WoojinValue::Double(a), WoojinValue::Double(b)) => Ok(WoojinValue::Bool(a <= b)),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => Ok(WoojinValue::Bool(a <= b)),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => Ok(WoojinValue::Bool(a <= b)),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => Ok(WoojinValue::Bool(a <= b)),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => Ok(WoojinValue::Bool(a <= b)),
       _ => Err(WoojinError::new("The type that can't be compared", crate::error::WoojinErrorKind::CannotCompare))
     }
   }
 
   fn add(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Int(a + b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Float(a + b)),
-      (WoojinValue::Float(a), WoojinValue::Int(b)) => Ok(WoojinValue::Float(a + (b as f32))),
-      (WoojinValue::Int(a), WoojinValue::Float(b)) => Ok(WoojinValue::Float((a as f32) + b)),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => return a.checked_add(b).map(WoojinValue::Int).ok_or_else(|| overflow("added")),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => return a.checked_add(b).map(WoojinValue::Long).ok_or_else(|| overflow("added")),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => return Ok(WoojinValue::Float(a + b)),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => return Ok(WoojinValue::Double(a + b)),
+      _ => {}
+    }
+    match (self.clone(), other.clone()) {
       (WoojinValue::String(a), WoojinValue::String(b)) => Ok(WoojinValue::String(format!("{}{}", a, b))),
+      (WoojinValue::Array(a), WoojinValue::Array(b)) => Ok(WoojinValue::Array([a, b].concat())),
       _ => Err(WoojinError::new("The type that can't be added", crate::error::WoojinErrorKind::CannotAdd)),
-    }  
+    }
   }
 
   fn sub(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Int(a - b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Float(a - b)),
-      (WoojinValue::Float(a), WoojinValue::Int(b)) => Ok(WoojinValue::Float(a - (b as f32))),
-      (WoojinValue::Int(a), WoojinValue::Float(b)) => Ok(WoojinValue::Float((a as f32) - b)),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => a.checked_sub(b).map(WoojinValue::Int).ok_or_else(|| overflow("subtracted")),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => a.checked_sub(b).map(WoojinValue::Long).ok_or_else(|| overflow("subtracted")),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => Ok(WoojinValue::Float(a - b)),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => Ok(WoojinValue::Double(a - b)),
       _ => Err(WoojinError::new("The type that can't be subtracted", crate::error::WoojinErrorKind::CannotSubtract)),
-    }  
+    }
   }
 
   fn mul(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(WoojinValue::Int(a * b)),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(WoojinValue::Float(a * b)),
-      (WoojinValue::Float(a), WoojinValue::Int(b)) => Ok(WoojinValue::Float(a * (b as f32))),
-      (WoojinValue::Int(a), WoojinValue::Float(b)) => Ok(WoojinValue::Float((a as f32) * b)),
-      (WoojinValue::String(left), WoojinValue::Int(right)) => Ok(WoojinValue::String(left.as_str().repeat(right as usize))),
-      (WoojinValue::String(left), WoojinValue::Long(right)) => Ok(WoojinValue::String(left.as_str().repeat(right as usize))),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => return a.checked_mul(b).map(WoojinValue::Int).ok_or_else(|| overflow("multiplied")),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => return a.checked_mul(b).map(WoojinValue::Long).ok_or_else(|| overflow("multiplied")),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => return Ok(WoojinValue::Float(a * b)),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => return Ok(WoojinValue::Double(a * b)),
+      _ => {}
+    }
+    match (self.clone(), other.clone()) {
+      (WoojinValue::String(left), WoojinValue::Int(right)) => repeat(&left, right as i64),
+      (WoojinValue::String(left), WoojinValue::Long(right)) => repeat(&left, right),
       _ => Err(WoojinError::new("The type that can't be multiplied!", crate::error::WoojinErrorKind::CannotMultiply)),
     }
   }
@@ -229,12 +436,128 @@ impl ValueCalc for WoojinValue {
   fn div(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
     #[allow(non_snake_case)]
     let DivisionZeroError: WoojinError = WoojinError::new("It cannot be divided by 0.0", crate::error::WoojinErrorKind::DivisionByZero);
-    match (self.value(), other.value()) {
-      (WoojinValue::Int(a), WoojinValue::Int(b)) => Ok(if b != 0 { WoojinValue::Int(a / b) } else { return Err(DivisionZeroError) }),
-      (WoojinValue::Float(a), WoojinValue::Float(b)) => Ok(if b != 0.0 { WoojinValue::Float(a / b) } else {return Err(DivisionZeroError)}),
-      (WoojinValue::Float(a), WoojinValue::Int(b)) => Ok(if b != 0 { WoojinValue::Float(a / (b as f32)) } else {return Err(DivisionZeroError)}),
-      (WoojinValue::Int(a), WoojinValue::Float(b)) => Ok(if b != 0.0 { WoojinValue::Float((a as f32) / b) } else {return Err(DivisionZeroError)}),
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => if b != 0 { a.checked_div(b).map(WoojinValue::Int).ok_or_else(|| overflow("divided")) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => if b != 0 { a.checked_div(b).map(WoojinValue::Long).ok_or_else(|| overflow("divided")) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => Ok(if b != 0.0 { WoojinValue::Float(a / b) } else { return Err(DivisionZeroError) }),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => Ok(if b != 0.0 { WoojinValue::Double(a / b) } else { return Err(DivisionZeroError) }),
+      _ => Err(WoojinError::new("an indivisible type!", crate::error::WoojinErrorKind::CannotDivide)),
+    }
+  }
+
+  // The sign of the result follows the divisor, so that a == (a // b) * b + a % b
+  fn rem(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
+    #[allow(non_snake_case)]
+    let DivisionZeroError: WoojinError = WoojinError::new("It cannot be divided by 0.0", crate::error::WoojinErrorKind::DivisionByZero);
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => if b != 0 { a.checked_rem(b).map(|r| WoojinValue::Int(if r != 0 && (r < 0) != (b < 0) { r + b } else { r })).ok_or_else(|| overflow("divided")) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => if b != 0 { a.checked_rem(b).map(|r| WoojinValue::Long(if r != 0 && (r < 0) != (b < 0) { r + b } else { r })).ok_or_else(|| overflow("divided")) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => if b != 0.0 { Ok(WoojinValue::Float(a - b * (a / b).floor())) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => if b != 0.0 { Ok(WoojinValue::Double(a - b * (a / b).floor())) } else { Err(DivisionZeroError) },
       _ => Err(WoojinError::new("an indivisible type!", crate::error::WoojinErrorKind::CannotDivide)),
     }
   }
-}
\ No newline at end of file
+
+  // An integer raised to a negative power gives a double, e.g. 2 ** -1 == 0.5,
+  // and 0 raised to a negative power divides by zero.
+  fn pow(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
+    #[allow(non_snake_case)]
+    let DivisionZeroError: WoojinError = WoojinError::new("0 cannot be raised to a negative power", crate::error::WoojinErrorKind::DivisionByZero);
+    match promote(self, other) {
+      Some((WoojinValue::Int(0), WoojinValue::Int(b))) if b < 0 => Err(DivisionZeroError),
+      Some((WoojinValue::Long(0), WoojinValue::Long(b))) if b < 0 => Err(DivisionZeroError),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) if a == 0.0 && b < 0.0 => Err(DivisionZeroError),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) if a == 0.0 && b < 0.0 => Err(DivisionZeroError),
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) if b < 0 => Ok(WoojinValue::Double((a as f64).powi(b))),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) if b < 0 => Ok(WoojinValue::Double((a as f64).powf(b as f64))),
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => a.checked_pow(b as u32).map(WoojinValue::Int).ok_or_else(|| overflow("raised to a power")),
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)).map(WoojinValue::Long).ok_or_else(|| overflow("raised to a power")),
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => Ok(WoojinValue::Float(a.powf(b))),
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => Ok(WoojinValue::Double(a.powf(b))),
+      _ => Err(WoojinError::new("The type that can't be raised to a power", crate::error::WoojinErrorKind::CannotExponentiate)),
+    }
+  }
+
+  // Rounds towards negative infinity, unlike `/` on integers which truncates
+  fn floor_div(&self, other: &WoojinValue) -> WoojinResult<WoojinValue> {
+    #[allow(non_snake_case)]
+    let DivisionZeroError: WoojinError = WoojinError::new("It cannot be divided by 0.0", crate::error::WoojinErrorKind::DivisionByZero);
+    match promote(self, other) {
+      Some((WoojinValue::Int(a), WoojinValue::Int(b))) => if b != 0 { a.checked_div(b).map(|q| WoojinValue::Int(if a % b != 0 && (a < 0) != (b < 0) { q - 1 } else { q })).ok_or_else(|| overflow("divided")) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Long(a), WoojinValue::Long(b))) => if b != 0 { a.checked_div(b).map(|q| WoojinValue::Long(if a % b != 0 && (a < 0) != (b < 0) { q - 1 } else { q })).ok_or_else(|| overflow("divided")) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Float(a), WoojinValue::Float(b))) => if b != 0.0 { Ok(WoojinValue::Float((a / b).floor())) } else { Err(DivisionZeroError) },
+      Some((WoojinValue::Double(a), WoojinValue::Double(b))) => if b != 0.0 { Ok(WoojinValue::Double((a / b).floor())) } else { Err(DivisionZeroError) },
+      _ => Err(WoojinError::new("an indivisible type!", crate::error::WoojinErrorKind::CannotDivide)),
+    }
+  }
+
+  fn neg(&self) -> WoojinResult<WoojinValue> {
+    match self {
+      WoojinValue::Int(a) => a.checked_neg().map(WoojinValue::Int).ok_or_else(|| overflow("negated")),
+      WoojinValue::Long(a) => a.checked_neg().map(WoojinValue::Long).ok_or_else(|| overflow("negated")),
+      WoojinValue::Float(a) => Ok(WoojinValue::Float(-a)),
+      WoojinValue::Double(a) => Ok(WoojinValue::Double(-a)),
+      _ => Err(WoojinError::new("The type that can't be negated", crate::error::WoojinErrorKind::CannotSubtract)),
+    }
+  }
+
+  fn index(&self, index: &WoojinValue) -> WoojinResult<WoojinValue> {
+    let values: &Vec<WoojinValue> = match self {
+      WoojinValue::Array(values) => values,
+      _ => return Err(WoojinError::new(format!("Cannot index into {}", self.kind()), crate::error::WoojinErrorKind::TypeMismatch)),
+    };
+    let i: usize = array_index(index, values.len())?;
+    Ok(values[i].clone())
+  }
+
+  // Only caught errors have fields: `kind`, `message` and `code`.
+  fn field(&self, name: &str) -> WoojinResult<WoojinValue> {
+    match (self, name) {
+      (WoojinValue::Error { kind, message: _ }, "kind") => Ok(WoojinValue::String(format!("{:?}", kind))),
+      (WoojinValue::Error { kind: _, message }, "message") => Ok(WoojinValue::String(message.clone())),
+      (WoojinValue::Error { kind, message: _ }, "code") => Ok(kind.code().map_or(WoojinValue::Unit, WoojinValue::Int)),
+      _ => Err(WoojinError::new(format!("{} has no field {}", self.kind(), name), crate::error::WoojinErrorKind::TypeMismatch)),
+    }
+  }
+}
+
+/// Arrays are equal when their elements are, compared like `==` compares them, so `[1] == [1L]`.
+fn array_equal(a: &[WoojinValue], b: &[WoojinValue]) -> WoojinResult<WoojinValue> {
+  if a.len() != b.len() { return Ok(WoojinValue::Bool(false)); }
+  for (a, b) in a.iter().zip(b) {
+    if a.equal(b)? != WoojinValue::Bool(true) { return Ok(WoojinValue::Bool(false)); }
+  }
+  Ok(WoojinValue::Bool(true))
+}
+
+fn overflow(action: &str) -> WoojinError {
+  WoojinError::new(format!("Integer overflow: the result doesn't fit in its type when {}", action), crate::error::WoojinErrorKind::IntegerOverflow)
+}
+
+/// Repeats a string `count` times. A result too long to build is an error instead of a panic.
+fn repeat(text: &str, count: i64) -> WoojinResult<WoojinValue> {
+  if count < 0 {
+    return Err(WoojinError::new(format!("A string can't be repeated a negative number of times ({})", count), crate::error::WoojinErrorKind::CannotMultiply));
+  }
+  let len: usize = match usize::try_from(count).ok().and_then(|count| text.len().checked_mul(count)) {
+    Some(len) if len <= isize::MAX as usize => len,
+    _ => return Err(overflow("multiplied")),
+  };
+  let mut result: String = String::new();
+  if len == 0 { return Ok(WoojinValue::String(result)); }
+  if result.try_reserve_exact(len).is_err() {
+    return Err(WoojinError::new(format!("A string of {} bytes is too long to build", len), crate::error::WoojinErrorKind::CannotMultiply));
+  }
+  result.extend(std::iter::repeat_n(text, count as usize));
+  Ok(WoojinValue::String(result))
+}
+
+/// Checks that `index` is an integer inside `0..len` and converts it.
+pub(crate) fn array_index(index: &WoojinValue, len: usize) -> WoojinResult<usize> {
+  match index {
+    WoojinValue::Int(i) if *i >= 0 && (*i as usize) < len => Ok(*i as usize),
+    WoojinValue::Long(i) if *i >= 0 && (*i as u64) < len as u64 => Ok(*i as usize),
+    WoojinValue::Int(_) | WoojinValue::Long(_) => Err(WoojinError::new(format!("Index {} is out of bounds for an array of length {}", index.to_print(), len), crate::error::WoojinErrorKind::IndexOutOfBounds)),
+    _ => Err(WoojinError::new(format!("The index of an array must be an integer, not {}", index.kind()), crate::error::WoojinErrorKind::TypeMismatch)),
+  }
+}
@@ -0,0 +1,141 @@
+/// Where the program to run comes from.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Source {
+  File(String),
+  /// `-` as the file: the program is read from standard input.
+  Stdin,
+  /// `-e <code>`.
+  Inline(String),
+}
+
+/// What the command line asked for.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Command {
+  Repl,
+  Run { source: Source, args: Vec<String>, any_extension: bool, allow_env: bool },
+  Explain(String),
+  Help,
+  Version,
+}
+
+pub(crate) const USAGE: &str = "\
+Usage:
+  woojin [options] [run] <file> [-- args...]
+  woojin [options] -e <code> [-- args...]
+  woojin [repl]
+  woojin --explain <error code>
+
+<file> can be - to read the program from standard input.
+Everything after the file or the code is passed to the program as $args.
+
+Options:
+  -e, --eval <code>          Run <code> instead of a file
+      --any-extension        Run files whose names don't end in .wj or .woojin
+      --allow-env            Let the program set environment variables with setenv
+      --explain <error code> Describe an error code, such as WJ13
+  -h, --help                 Print this help
+  -V, --version              Print the version";
+
+/// Parses the arguments after the program name. Options have to come before the file,
+/// so that everything after it can be handed to the script untouched.
+/// `run` and `repl` are only subcommands in place of the file; `./run` runs a file named run.
+pub(crate) fn parse(args: &[String]) -> Result<Command, String> {
+  let mut args = args.iter();
+  let mut any_extension: bool = false;
+  let mut allow_env: bool = false;
+  while let Some(arg) = args.next() {
+    let source: Source = match arg.as_str() {
+      "-h" | "--help" => return Ok(Command::Help),
+      "-V" | "--version" => return Ok(Command::Version),
+      "--explain" => return match args.next() {
+        Some(code) => Ok(Command::Explain(code.clone())),
+        None => Err("--explain needs an error code, such as WJ13".to_string())
+      },
+      "--any-extension" => { any_extension = true; continue; },
+      "--allow-env" => { allow_env = true; continue; },
+      "-e" | "--eval" => match args.next() {
+        Some(code) => Source::Inline(code.clone()),
+        None => return Err(format!("{} needs the code to run", arg))
+      },
+      "repl" => return match args.next() {
+        Some(arg) => Err(format!("unexpected argument {:?} after repl", arg)),
+        None => Ok(Command::Repl)
+      },
+      "run" => match args.next() {
+        Some(path) => file(path),
+        None => return Err("run needs a file to run".to_string())
+      },
+      "-" => Source::Stdin,
+      option if option.starts_with('-') => return Err(format!("unknown option {:?}", option)),
+      path => file(path),
+    };
+    let mut rest: Vec<String> = args.cloned().collect();
+    if rest.first().is_some_and(|arg| arg == "--") { rest.remove(0); }
+    return Ok(Command::Run { source, args: rest, any_extension, allow_env });
+  }
+  if any_extension || allow_env { return Err("no file to run".to_string()); }
+  Ok(Command::Repl)
+}
+
+fn file(path: &str) -> Source {
+  if path == "-" { Source::Stdin } else { Source::File(path.to_string()) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse, Command, Source};
+
+  fn parse_args(args: &[&str]) -> Result<Command, String> {
+    parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>())
+  }
+
+  fn run(source: Source, args: &[&str]) -> Command {
+    Command::Run { source, args: args.iter().map(|arg| arg.to_string()).collect(), any_extension: false, allow_env: false }
+  }
+
+  #[test]
+  fn no_arguments_or_repl_start_the_repl() {
+    assert_eq!(parse_args(&[]), Ok(Command::Repl));
+    assert_eq!(parse_args(&["repl"]), Ok(Command::Repl));
+    assert!(parse_args(&["repl", "main.wj"]).is_err());
+  }
+
+  #[test]
+  fn a_dash_reads_the_program_from_stdin() {
+    assert_eq!(parse_args(&["-"]), Ok(run(Source::Stdin, &[])));
+    assert_eq!(parse_args(&["run", "-", "a"]), Ok(run(Source::Stdin, &["a"])));
+  }
+
+  #[test]
+  fn everything_after_the_file_goes_to_the_program() {
+    assert_eq!(parse_args(&["main.wj", "--", "-e", "--help"]), Ok(run(Source::File("main.wj".to_string()), &["-e", "--help"])));
+    assert_eq!(parse_args(&["main.wj", "a", "--", "b"]), Ok(run(Source::File("main.wj".to_string()), &["a", "--", "b"])));
+    assert_eq!(parse_args(&["-e", "1", "--", "x"]), Ok(run(Source::Inline("1".to_string()), &["x"])));
+  }
+
+  #[test]
+  fn run_is_only_a_subcommand_in_place_of_the_file() {
+    assert_eq!(parse_args(&["run", "main.wj"]), Ok(run(Source::File("main.wj".to_string()), &[])));
+    assert_eq!(parse_args(&["run", "run"]), Ok(run(Source::File("run".to_string()), &[])));
+    assert_eq!(parse_args(&["main.wj", "run"]), Ok(run(Source::File("main.wj".to_string()), &["run"])));
+    assert!(parse_args(&["run"]).is_err());
+  }
+
+  #[test]
+  fn options_come_before_the_file() {
+    assert_eq!(
+      parse_args(&["--allow-env", "--any-extension", "x.txt"]),
+      Ok(Command::Run { source: Source::File("x.txt".to_string()), args: vec![], any_extension: true, allow_env: true })
+    );
+    assert!(parse_args(&["--allow-env"]).is_err());
+  }
+
+  #[test]
+  fn missing_values_and_unknown_options_are_errors() {
+    assert!(parse_args(&["-e"]).is_err());
+    assert!(parse_args(&["--eval"]).is_err());
+    assert!(parse_args(&["--explain"]).is_err());
+    assert!(parse_args(&["--frobnicate", "main.wj"]).is_err());
+    assert_eq!(parse_args(&["--explain", "WJ13"]), Ok(Command::Explain("WJ13".to_string())));
+  }
+}
@@ -0,0 +1,94 @@
+use super::WoojinErrorKind;
+
+impl WoojinErrorKind {
+  /// Every kind of error, in the order of their codes.
+  pub const ALL: [WoojinErrorKind; 36] = [
+    WoojinErrorKind::Roar,
+    WoojinErrorKind::Success,
+    WoojinErrorKind::Unknown,
+    WoojinErrorKind::UnknownToken,
+    WoojinErrorKind::FileNotFound,
+    WoojinErrorKind::UnsupportedExtension,
+    WoojinErrorKind::FailReadFailure,
+    WoojinErrorKind::UndeclaredVariable,
+    WoojinErrorKind::VariableAlreadyDeclared,
+    WoojinErrorKind::VariableNotMutable,
+    WoojinErrorKind::ParseError,
+    WoojinErrorKind::CannotAdd,
+    WoojinErrorKind::CannotSubtract,
+    WoojinErrorKind::CannotMultiply,
+    WoojinErrorKind::CannotDivide,
+    WoojinErrorKind::DivisionByZero,
+    WoojinErrorKind::InvalidType,
+    WoojinErrorKind::TypeMismatch,
+    WoojinErrorKind::InvalidIndent,
+    WoojinErrorKind::IfParsingFailed,
+    WoojinErrorKind::ElseParsingFailed,
+    WoojinErrorKind::InvaildAssignment,
+    WoojinErrorKind::CannotCompare,
+    WoojinErrorKind::WhileParsingFailed,
+    WoojinErrorKind::LoopControlOutsideLoop,
+    WoojinErrorKind::ForParsingFailed,
+    WoojinErrorKind::FunctionParsingFailed,
+    WoojinErrorKind::UndeclaredFunction,
+    WoojinErrorKind::ArgumentMismatch,
+    WoojinErrorKind::ReturnOutsideFunction,
+    WoojinErrorKind::StackOverflow,
+    WoojinErrorKind::IndexOutOfBounds,
+    WoojinErrorKind::IntegerOverflow,
+    WoojinErrorKind::CannotExponentiate,
+    WoojinErrorKind::TryParsingFailed,
+    WoojinErrorKind::PermissionDenied,
+  ];
+
+  /// Looks up an error by its code, written either as `13` or as `WJ13`.
+  pub fn from_code(code: &str) -> Option<WoojinErrorKind> {
+    let digits: &str = code.strip_prefix("WJ").or_else(|| code.strip_prefix("wj")).unwrap_or(code);
+    let code: i32 = digits.parse().ok()?;
+    WoojinErrorKind::ALL.into_iter().find(|kind| kind.code() == Some(code))
+  }
+
+  /// A longer description of the error with examples, as shown by `woojin --explain`.
+  /// The texts live in `src/error/codes`, one Markdown file per code.
+  pub fn explain(&self) -> Option<&'static str> {
+    match self {
+      WoojinErrorKind::Roar => Some(include_str!("codes/WJ0.md")),
+      WoojinErrorKind::Success => Some(include_str!("codes/WJ1.md")),
+      WoojinErrorKind::Unknown => Some(include_str!("codes/WJ2.md")),
+      WoojinErrorKind::UnknownToken => Some(include_str!("codes/WJ3.md")),
+      WoojinErrorKind::FileNotFound => Some(include_str!("codes/WJ4.md")),
+      WoojinErrorKind::UnsupportedExtension => Some(include_str!("codes/WJ5.md")),
+      WoojinErrorKind::FailReadFailure => Some(include_str!("codes/WJ6.md")),
+      WoojinErrorKind::UndeclaredVariable => Some(include_str!("codes/WJ7.md")),
+      WoojinErrorKind::VariableAlreadyDeclared => Some(include_str!("codes/WJ8.md")),
+      WoojinErrorKind::VariableNotMutable => Some(include_str!("codes/WJ9.md")),
+      WoojinErrorKind::ParseError => Some(include_str!("codes/WJ10.md")),
+      WoojinErrorKind::CannotAdd => Some(include_str!("codes/WJ11.md")),
+      WoojinErrorKind::CannotSubtract => Some(include_str!("codes/WJ12.md")),
+      WoojinErrorKind::CannotMultiply => Some(include_str!("codes/WJ13.md")),
+      WoojinErrorKind::CannotDivide => Some(include_str!("codes/WJ14.md")),
+      WoojinErrorKind::DivisionByZero => Some(include_str!("codes/WJ15.md")),
+      WoojinErrorKind::InvalidType => Some(include_str!("codes/WJ16.md")),
+      WoojinErrorKind::TypeMismatch => Some(include_str!("codes/WJ17.md")),
+      WoojinErrorKind::InvalidIndent => Some(include_str!("codes/WJ18.md")),
+      WoojinErrorKind::IfParsingFailed => Some(include_str!("codes/WJ19.md")),
+      WoojinErrorKind::ElseParsingFailed => Some(include_str!("codes/WJ20.md")),
+      WoojinErrorKind::InvaildAssignment => Some(include_str!("codes/WJ21.md")),
+      WoojinErrorKind::CannotCompare => Some(include_str!("codes/WJ22.md")),
+      WoojinErrorKind::WhileParsingFailed => Some(include_str!("codes/WJ23.md")),
+      WoojinErrorKind::LoopControlOutsideLoop => Some(include_str!("codes/WJ24.md")),
+      WoojinErrorKind::ForParsingFailed => Some(include_str!("codes/WJ25.md")),
+      WoojinErrorKind::FunctionParsingFailed => Some(include_str!("codes/WJ26.md")),
+      WoojinErrorKind::UndeclaredFunction => Some(include_str!("codes/WJ27.md")),
+      WoojinErrorKind::ArgumentMismatch => Some(include_str!("codes/WJ28.md")),
+      WoojinErrorKind::ReturnOutsideFunction => Some(include_str!("codes/WJ29.md")),
+      WoojinErrorKind::StackOverflow => Some(include_str!("codes/WJ30.md")),
+      WoojinErrorKind::IndexOutOfBounds => Some(include_str!("codes/WJ31.md")),
+      WoojinErrorKind::IntegerOverflow => Some(include_str!("codes/WJ32.md")),
+      WoojinErrorKind::CannotExponentiate => Some(include_str!("codes/WJ33.md")),
+      WoojinErrorKind::TryParsingFailed => Some(include_str!("codes/WJ34.md")),
+      WoojinErrorKind::PermissionDenied => Some(include_str!("codes/WJ35.md")),
+      WoojinErrorKind::Exit(_) => None,
+    }
+  }
+}
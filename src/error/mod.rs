@@ -0,0 +1,231 @@
+use std::{error::Error, io::IsTerminal};
+
+mod explain;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WoojinErrorKind {
+  Roar,
+  Success,
+  Unknown,
+  UnknownToken,
+  FileNotFound,
+  UnsupportedExtension,
+  FailReadFailure,
+  UndeclaredVariable,
+  VariableAlreadyDeclared,
+  VariableNotMutable,
+  ParseError,
+  CannotAdd,
+  CannotSubtract,
+  CannotMultiply,
+  CannotDivide,
+  DivisionByZero,
+  InvalidType,
+  TypeMismatch,
+  InvalidIndent,
+  IfParsingFailed,
+  ElseParsingFailed,
+  InvaildAssignment,
+  CannotCompare,
+  WhileParsingFailed,
+  LoopControlOutsideLoop,
+  ForParsingFailed,
+  FunctionParsingFailed,
+  UndeclaredFunction,
+  ArgumentMismatch,
+  ReturnOutsideFunction,
+  StackOverflow,
+  IndexOutOfBounds,
+  IntegerOverflow,
+  CannotExponentiate,
+  TryParsingFailed,
+  PermissionDenied,
+  /// Not an error: the program ran `yee <code>` and wants to stop with that exit code.
+  Exit(i32)
+}
+
+impl WoojinErrorKind {
+  /// The number shown as `WJ<code>`. Codes are stable: a code is never renumbered or reused,
+  /// and a new kind of error takes the next free number. `Exit` is not an error and has no code.
+  pub fn code(&self) -> Option<i32> {
+    let code: i32 = match self {
+      WoojinErrorKind::Roar => 0,
+      WoojinErrorKind::Success => 1,
+      WoojinErrorKind::Unknown => 2,
+      WoojinErrorKind::UnknownToken => 3,
+      WoojinErrorKind::FileNotFound => 4,
+      WoojinErrorKind::UnsupportedExtension => 5,
+      WoojinErrorKind::FailReadFailure => 6,
+      WoojinErrorKind::UndeclaredVariable => 7,
+      WoojinErrorKind::VariableAlreadyDeclared => 8,
+      WoojinErrorKind::VariableNotMutable => 9,
+      WoojinErrorKind::ParseError => 10,
+      WoojinErrorKind::CannotAdd => 11,
+      WoojinErrorKind::CannotSubtract => 12,
+      WoojinErrorKind::CannotMultiply => 13,
+      WoojinErrorKind::CannotDivide => 14,
+      WoojinErrorKind::DivisionByZero => 15,
+      WoojinErrorKind::InvalidType => 16,
+      WoojinErrorKind::TypeMismatch => 17,
+      WoojinErrorKind::InvalidIndent => 18,
+      WoojinErrorKind::IfParsingFailed => 19,
+      WoojinErrorKind::ElseParsingFailed => 20,
+      WoojinErrorKind::InvaildAssignment => 21,
+      WoojinErrorKind::CannotCompare => 22,
+      WoojinErrorKind::WhileParsingFailed => 23,
+      WoojinErrorKind::LoopControlOutsideLoop => 24,
+      WoojinErrorKind::ForParsingFailed => 25,
+      WoojinErrorKind::FunctionParsingFailed => 26,
+      WoojinErrorKind::UndeclaredFunction => 27,
+      WoojinErrorKind::ArgumentMismatch => 28,
+      WoojinErrorKind::ReturnOutsideFunction => 29,
+      WoojinErrorKind::StackOverflow => 30,
+      WoojinErrorKind::IndexOutOfBounds => 31,
+      WoojinErrorKind::IntegerOverflow => 32,
+      WoojinErrorKind::CannotExponentiate => 33,
+      WoojinErrorKind::TryParsingFailed => 34,
+      WoojinErrorKind::PermissionDenied => 35,
+      WoojinErrorKind::Exit(_) => return None,
+    };
+    Some(code)
+  }
+}
+
+/// Where a piece of source code was written. Lines and columns start at 1,
+/// and columns and lengths are counted in characters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Span {
+  pub line: usize,
+  pub column: usize,
+  pub len: usize
+}
+
+impl Span {
+  /// The span from the start of `self` to the end of `other`.
+  pub fn to(self, other: Span) -> Span {
+    if other.line != self.line { return self; }
+    Span { len: (other.column + other.len).saturating_sub(self.column), ..self }
+  }
+}
+
+#[derive(Debug)]
+pub struct WoojinError {
+  pub details: String,
+  pub kind: WoojinErrorKind,
+  pub span: Option<Span>,
+  pub file: Option<Box<str>>,
+  /// The source line `span` points into, shown under the message.
+  pub source_line: Option<Box<str>>,
+  pub help: Option<Box<str>>,
+  /// The exit status the program stops with because of this error, if not the default of 1.
+  pub status: Option<i32>
+}
+
+impl WoojinError {
+  pub fn new(msg: impl ToString, kind: WoojinErrorKind) -> WoojinError {
+    WoojinError{
+      details: msg.to_string(),
+      kind,
+      span: None,
+      file: None,
+      source_line: None,
+      help: None,
+      status: None
+    }
+  }
+
+  /// Points the error at `span`, unless it already points at something more specific.
+  pub fn with_span(mut self, span: Span) -> WoojinError {
+    if self.span.is_none() { self.span = Some(span); }
+    self
+  }
+
+  pub fn with_file(mut self, file: impl ToString) -> WoojinError {
+    self.file = Some(file.to_string().into_boxed_str());
+    self
+  }
+
+  /// Keeps the line of `src` the error points at, so it can be shown in the report.
+  pub fn with_source(mut self, src: &str) -> WoojinError {
+    if let (None, Some(span)) = (&self.source_line, self.span) {
+      self.source_line = src.lines().nth(span.line.saturating_sub(1)).map(Box::from);
+    }
+    self
+  }
+
+  pub fn with_help(mut self, help: impl ToString) -> WoojinError {
+    self.help = Some(help.to_string().into_boxed_str());
+    self
+  }
+
+  pub fn with_status(mut self, status: i32) -> WoojinError {
+    self.status = Some(status);
+    self
+  }
+
+  /// Returns the requested exit code if this "error" is a `yee` rather than a failure.
+  pub fn exit_code(&self) -> Option<i32> {
+    match self.kind {
+      WoojinErrorKind::Exit(code) => Some(code),
+      _ => None
+    }
+  }
+
+  pub fn exit(&self) -> ! {
+    if let Some(code) = self.exit_code() { std::process::exit(code); }
+    println!("{}", self);
+    std::process::exit(self.status.unwrap_or(1));
+  }
+}
+
+impl Error for WoojinError {}
+
+/// Whether reports may use ANSI colours: only on a terminal, and never when `NO_COLOR` is set.
+fn use_colour() -> bool {
+  std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && std::io::stdout().is_terminal()
+}
+
+/// Renders a rustc-style report:
+///
+/// ```text
+/// error[WJ7]: Variable b is not declared
+///  --> main.wj:2:9
+///   |
+/// 2 | println $b + 1
+///   |         ^^
+///   = help: declare it first, e.g. `let b = 0`
+/// ```
+impl std::fmt::Display for WoojinError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let (bold, red, blue, reset): (&str, &str, &str, &str) = if use_colour() {
+      ("\x1b[1m", "\x1b[1m\x1b[31m", "\x1b[1m\x1b[34m", "\x1b[0m")
+    } else {
+      ("", "", "", "")
+    };
+    match self.kind.code() {
+      Some(code) => write!(f, "{}error[WJ{}]{}{}: {}{}", red, code, reset, bold, self.details, reset)?,
+      None => write!(f, "{}error{}{}: {}{}", red, reset, bold, self.details, reset)?
+    }
+    let gutter: String = " ".repeat(self.span.map_or(1, |span| span.line.to_string().len()));
+    match (&self.file, self.span) {
+      (Some(file), Some(span)) => write!(f, "\n{}{}-->{} {}:{}:{}", gutter, blue, reset, file, span.line, span.column)?,
+      (Some(file), None) => write!(f, "\n{}{}-->{} {}", gutter, blue, reset, file)?,
+      (None, Some(span)) => write!(f, "\n{}{}-->{} line {}, column {}", gutter, blue, reset, span.line, span.column)?,
+      (None, None) => {}
+    }
+    if let (Some(line), Some(span)) = (&self.source_line, self.span) {
+      // Tabs are shown as four spaces so the carets stay under the right characters.
+      let text: String = line.replace('\t', "    ");
+      let width = |c: char| if c == '\t' { 4 } else { 1 };
+      let padding: String = " ".repeat(line.chars().take(span.column - 1).map(width).sum());
+      let carets: usize = line.chars().skip(span.column - 1).take(span.len).map(width).sum();
+      write!(f, "\n{} {}|{}", gutter, blue, reset)?;
+      write!(f, "\n{}{} |{} {}", blue, span.line, reset, text)?;
+      write!(f, "\n{} {}|{} {}{}{}{}", gutter, blue, reset, padding, red, "^".repeat(carets.max(1)), reset)?;
+    }
+    if let Some(help) = &self.help {
+      write!(f, "\n{} {}={} {}help{}: {}", gutter, blue, reset, bold, reset, help)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+
+use nom::{
+  IResult,
+  bytes::complete::{take_while, take_while1},
+  character::complete::{char, digit1},
+  combinator::{opt, recognize},
+  sequence::{pair, tuple},
+};
+
+use crate::error::{WoojinError, WoojinErrorKind, Span};
+
+/// A word with a meaning of its own. Keywords can't be used as function names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Keyword {
+  Let,
+  Mut,
+  If,
+  Else,
+  While,
+  For,
+  In,
+  Fn,
+  Return,
+  Break,
+  Continue,
+  Try,
+  Catch,
+  Print,
+  Println,
+  Roar,
+  Yee,
+  Input,
+  Sleep,
+  Env,
+  SetEnv,
+  And,
+  Or,
+  Not,
+}
+
+impl Keyword {
+  const ALL: [(&'static str, Keyword); 24] = [
+    ("let", Keyword::Let),
+    ("mut", Keyword::Mut),
+    ("if", Keyword::If),
+    ("else", Keyword::Else),
+    ("while", Keyword::While),
+    ("for", Keyword::For),
+    ("in", Keyword::In),
+    ("fn", Keyword::Fn),
+    ("return", Keyword::Return),
+    ("break", Keyword::Break),
+    ("continue", Keyword::Continue),
+    ("try", Keyword::Try),
+    ("catch", Keyword::Catch),
+    ("print", Keyword::Print),
+    ("println", Keyword::Println),
+    ("roar", Keyword::Roar),
+    ("yee", Keyword::Yee),
+    ("input", Keyword::Input),
+    ("sleep", Keyword::Sleep),
+    ("env", Keyword::Env),
+    ("setenv", Keyword::SetEnv),
+    ("and", Keyword::And),
+    ("or", Keyword::Or),
+    ("not", Keyword::Not),
+  ];
+
+  fn from_word(word: &str) -> Option<Keyword> {
+    Keyword::ALL.iter().find(|(text, _)| *text == word).map(|(_, keyword)| *keyword)
+  }
+
+  pub(crate) fn as_str(self) -> &'static str {
+    Keyword::ALL.iter().find(|(_, keyword)| *keyword == self).map(|(text, _)| *text).unwrap()
+  }
+}
+
+/// Operators and punctuation, longest first so that `**` isn't read as two `*`.
+const SYMBOLS: [&str; 26] = [
+  "**", "//", "==", "!=", "<=", ">=", "&&", "||", "..",
+  "+", "-", "*", "/", "%", "=", "<", ">", "!",
+  "(", ")", "[", "]", ",", ":", ".", ";",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+  Keyword(Keyword),
+  /// A function or type name, or the name in `let`.
+  Ident(String),
+  /// `$name`, without the `$`.
+  Var(String),
+  /// A number as written, suffix included, e.g. `10L` or `1.5d`. Its type is decided by the parser.
+  Number(String),
+  /// A string literal with its escapes already replaced.
+  Str(String),
+  /// `uglyguri` or `beautifulguri`.
+  Bool(bool),
+  Symbol(&'static str),
+  Newline,
+  /// The start of a block: a line indented deeper than the one before it.
+  Indent,
+  /// The end of a block.
+  Dedent,
+  /// Stands in for a line that failed to lex. The lexer has already reported why,
+  /// so the parser skips the line without an error of its own.
+  Error,
+  Eof,
+}
+
+impl fmt::Display for TokenKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      TokenKind::Keyword(keyword) => write!(f, "`{}`", keyword.as_str()),
+      TokenKind::Ident(name) => write!(f, "`{}`", name),
+      TokenKind::Var(name) => write!(f, "`${}`", name),
+      TokenKind::Number(text) => write!(f, "`{}`", text),
+      TokenKind::Str(_) => write!(f, "a string"),
+      TokenKind::Bool(true) => write!(f, "`uglyguri`"),
+      TokenKind::Bool(false) => write!(f, "`beautifulguri`"),
+      TokenKind::Symbol(symbol) => write!(f, "`{}`", symbol),
+      TokenKind::Newline => write!(f, "the end of the line"),
+      TokenKind::Indent => write!(f, "an indented line"),
+      TokenKind::Dedent => write!(f, "the end of the block"),
+      TokenKind::Error => write!(f, "an invalid line"),
+      TokenKind::Eof => write!(f, "the end of the file"),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Token {
+  pub kind: TokenKind,
+  pub span: Span,
+}
+
+/// Splits source code into tokens. Every line ends with a `Newline`, blocks are
+/// marked with `Indent` and `Dedent` like in Python, and blank lines and lines with only
+/// a comment are skipped. Every line is lexed even after an error, so that all of them
+/// are reported at once, and a line with an error becomes a single `Error` token so that
+/// the other lines can still be parsed.
+pub(crate) fn lex(src: &str) -> (Vec<Token>, Vec<WoojinError>) {
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut errors: Vec<WoojinError> = Vec::new();
+  let mut unit: Option<IndentUnit> = None;
+  // How many blocks deep the last line was.
+  let mut depth: usize = 0;
+  let mut last_line: usize = 1;
+  for (i, text) in src.lines().enumerate() {
+    let number: usize = i + 1;
+    let code: &str = text.trim_start_matches([' ', '\t']);
+    if code.is_empty() || code.starts_with("//") { continue; }
+    last_line = number;
+    let indent: &str = &text[..text.len() - code.len()];
+    let start: Span = Span { line: number, column: indent.len() + 1, len: 1 };
+    let newline: Token = Token { kind: TokenKind::Newline, span: Span { line: number, column: text.chars().count() + 1, len: 1 } };
+
+    let level: usize = match indent_level(indent, &mut unit) {
+      Ok(level) => level,
+      Err(e) => {
+        errors.push(e.with_span(Span { line: number, column: 1, len: indent.len() }));
+        tokens.extend([Token { kind: TokenKind::Error, span: start }, newline]);
+        continue;
+      }
+    };
+    if level > depth + 1 {
+      errors.push(WoojinError::new(format!("This line is indented {} levels deeper than the line before it", level - depth), WoojinErrorKind::InvalidIndent)
+        .with_help("a block is indented one level deeper than the line that opens it")
+        .with_span(Span { line: number, column: 1, len: indent.len() }));
+      // The lines indented like this one are skipped along with it, as if they were its block.
+      tokens.extend([Token { kind: TokenKind::Error, span: start }, newline]);
+      for _ in depth..level { tokens.push(Token { kind: TokenKind::Indent, span: start }); }
+      depth = level;
+      continue;
+    } else if level == depth + 1 {
+      tokens.push(Token { kind: TokenKind::Indent, span: start });
+    } else {
+      for _ in level..depth { tokens.push(Token { kind: TokenKind::Dedent, span: start }); }
+    }
+    depth = level;
+
+    match lex_line(text, indent.len(), number) {
+      Ok(line) => tokens.extend(line),
+      Err(e) => { errors.push(e); tokens.push(Token { kind: TokenKind::Error, span: start }); }
+    }
+    tokens.push(newline);
+  }
+  let end: Span = Span { line: last_line, column: 1, len: 1 };
+  for _ in 0..depth { tokens.push(Token { kind: TokenKind::Dedent, span: end }); }
+  tokens.push(Token { kind: TokenKind::Eof, span: end });
+  (tokens, errors)
+}
+
+/// One level of indentation, taken from the first indented line of the file:
+/// a tab, or any number of spaces.
+#[derive(Debug, Clone, Copy)]
+struct IndentUnit {
+  tabs: bool,
+  width: usize,
+}
+
+impl fmt::Display for IndentUnit {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match (self.tabs, self.width) {
+      (true, 1) => write!(f, "a tab"),
+      (true, width) => write!(f, "{} tabs", width),
+      (false, 1) => write!(f, "1 space"),
+      (false, width) => write!(f, "{} spaces", width),
+    }
+  }
+}
+
+/// How many levels deep `indent`, the whitespace at the start of a line, is.
+/// The first indented line sets the unit that every other line has to be a multiple of.
+fn indent_level(indent: &str, unit: &mut Option<IndentUnit>) -> Result<usize, WoojinError> {
+  if indent.is_empty() { return Ok(0); }
+  let tabs: bool = indent.starts_with('\t');
+  if indent.contains(if tabs { ' ' } else { '\t' }) {
+    return Err(WoojinError::new("This line mixes tabs and spaces in its indentation", WoojinErrorKind::InvalidIndent)
+      .with_help("indent with either tabs or spaces, not both"));
+  }
+  let found: IndentUnit = IndentUnit { tabs, width: indent.len() };
+  let unit: IndentUnit = *unit.get_or_insert(found);
+  if tabs != unit.tabs {
+    return Err(WoojinError::new(format!("This line is indented with {}, but the file is indented with {}", if tabs { "tabs" } else { "spaces" }, unit), WoojinErrorKind::InvalidIndent)
+      .with_help(format!("the first indented line sets the indentation of the whole file, here {} per level", unit)));
+  }
+  if !found.width.is_multiple_of(unit.width) {
+    return Err(WoojinError::new(format!("This line is indented by {}, which is not a multiple of {}", found, unit), WoojinErrorKind::InvalidIndent)
+      .with_help(format!("the first indented line sets the indentation of the whole file, here {} per level", unit)));
+  }
+  Ok(found.width / unit.width)
+}
+
+/// Lexes the code of one line, which starts at byte `start` of `text`.
+fn lex_line(text: &str, start: usize, number: usize) -> Result<Vec<Token>, WoojinError> {
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut rest: &str = &text[start..];
+  // Counted as the line is walked, so that long lines don't take quadratic time.
+  let mut column: usize = text[..start].chars().count() + 1;
+  loop {
+    let code: &str = rest.trim_start_matches([' ', '\t']);
+    column += rest.len() - code.len();
+    rest = code;
+    if rest.is_empty() { break; }
+    let (after, kind): (&str, TokenKind) = match token(rest) {
+      Ok(token) => token,
+      Err((message, kind)) => return Err(WoojinError::new(message, kind).with_span(Span { line: number, column, len: 1 }))
+    };
+    let len: usize = rest[..rest.len() - after.len()].chars().count();
+    tokens.push(Token { kind, span: Span { line: number, column, len } });
+    column += len;
+    rest = after;
+  }
+  Ok(tokens)
+}
+
+type LexResult<'a> = Result<(&'a str, TokenKind), (String, WoojinErrorKind)>;
+
+fn token(input: &str) -> LexResult<'_> {
+  let first: char = input.chars().next().unwrap();
+  if first == '"' { return string(input); }
+  if let Ok((rest, text)) = number(input) { return Ok((rest, TokenKind::Number(text.to_string()))); }
+  if let Ok((rest, word)) = word(input) {
+    let kind: TokenKind = match word {
+      "uglyguri" => TokenKind::Bool(true),
+      "beautifulguri" => TokenKind::Bool(false),
+      word => match Keyword::from_word(word) {
+        Some(keyword) => TokenKind::Keyword(keyword),
+        None => TokenKind::Ident(word.to_string())
+      }
+    };
+    return Ok((rest, kind));
+  }
+  if let Some(name) = input.strip_prefix('$') {
+    let (rest, name): (&str, &str) = take_while1::<_, _, nom::error::Error<&str>>(|c: char| c.is_ascii_alphanumeric() || c == '_')(name)
+      .map_err(|_| ("Expected a variable name after `$`".to_string(), WoojinErrorKind::UnknownToken))?;
+    return Ok((rest, TokenKind::Var(name.to_string())));
+  }
+  match SYMBOLS.iter().find(|symbol| input.starts_with(**symbol)) {
+    Some(symbol) => Ok((&input[symbol.len()..], TokenKind::Symbol(symbol))),
+    None => Err((format!("Unknown character `{}`", first), WoojinErrorKind::UnknownToken))
+  }
+}
+
+/// Digits with an optional fraction, followed by any letters so that a suffix like `L`
+/// stays part of the number (and a bad one like `12abc` is reported as a bad number).
+fn number(input: &str) -> IResult<&str, &str> {
+  recognize(tuple((
+    digit1,
+    opt(pair(char('.'), digit1)),
+    take_while(|c: char| c.is_ascii_alphanumeric() || c == '_'),
+  )))(input)
+}
+
+fn word(input: &str) -> IResult<&str, &str> {
+  recognize(pair(
+    take_while1(|c: char| c.is_ascii_alphabetic() || c == '_'),
+    take_while(|c: char| c.is_ascii_alphanumeric() || c == '_'),
+  ))(input)
+}
+
+/// A string literal. The escapes are `\\`, `\"`, `\n`, `\t`, `\r` and `\0`.
+fn string(input: &str) -> LexResult<'_> {
+  let mut value: String = String::new();
+  let mut chars = input.char_indices().skip(1);
+  while let Some((i, c)) = chars.next() {
+    match c {
+      '"' => return Ok((&input[i + 1..], TokenKind::Str(value))),
+      '\\' => value.push(match chars.next() {
+        Some((_, '\\')) => '\\',
+        Some((_, '"')) => '"',
+        Some((_, 'n')) => '\n',
+        Some((_, 't')) => '\t',
+        Some((_, 'r')) => '\r',
+        Some((_, '0')) => '\0',
+        Some((_, other)) => return Err((format!("Unknown escape `\\{}` in a string", other), WoojinErrorKind::ParseError)),
+        None => break
+      }),
+      c => value.push(c)
+    }
+  }
+  Err(("This string is never closed".to_string(), WoojinErrorKind::ParseError))
+}
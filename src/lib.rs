@@ -4,129 +4,607 @@ pub(crate) mod variable;
 pub(crate) mod ast;
 pub(crate) mod parser;
 pub(crate) mod calc;
+pub(crate) mod lexer;
 
-use std::{io::{Write}};
-use ast::Statements;
-use calc::{ValueCalc, Calc};
-use error::WoojinError;
-use nom::IResult;
-use parser::{WoojinResult, tokenizer};
-use types::WoojinValue;
-use variable::WoojinVariable;
+use std::{io::Write, path::Path, collections::HashMap, sync::Arc};
+use ast::{Statements, Stmt, Iterable, Function};
+use calc::{ValueCalc, Calc, Expr, BinaryOp};
+use error::{WoojinError, WoojinErrorKind};
+use parser::{WoojinResult, parse};
+use types::{WoojinValue, WoojinValueKind};
+use variable::{Environment, VariableOption};
 
-pub(crate) type NomResult<'a, T> = IResult<&'a str, T>;
 // pub(crate) type StdString = std::string::String;
 
+/// How deep user-defined function calls may nest before the program is stopped, unless
+/// [`Interpreter::set_max_call_depth`] says otherwise.
+const MAX_CALL_DEPTH: usize = 1000;
+
+/// The stack of the thread a program runs on: a base for the code outside of functions,
+/// and enough for every call it may nest. A call costs about 11 KiB in a debug build,
+/// and about twice that when its body is nested a few blocks deep.
+const RUN_STACK: usize = 4 << 20;
+const CALL_STACK: usize = 128 << 10;
+
+/// A pending jump out of the current block, set by `break`/`continue`/`return`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Flow {
+  Break,
+  Continue,
+  Return(WoojinValue),
+}
+
 #[allow(dead_code)]
 pub(crate) struct Program {
   pub(crate) pointer: i32,
-  pub(crate) variables: Vec<WoojinVariable>,
-  pub(crate) statements: Vec<Statements>
+  pub(crate) variables: Environment,
+  /// Kept after a run, so that the same code can be run again.
+  pub(crate) statements: Arc<[Stmt]>,
+  /// The source code `statements` were parsed from.
+  pub(crate) source: Arc<str>,
+  pub(crate) functions: HashMap<String, Arc<Function>>,
+  pub(crate) flow: Option<Flow>,
+  pub(crate) call_depth: usize,
+  pub(crate) max_call_depth: usize,
+  /// Whether `setenv` may set environment variables.
+  pub(crate) env_writable: bool,
+  /// The variables set with `setenv`, which `env` reads before those of the process.
+  pub(crate) env_vars: HashMap<String, String>
 }
 
 impl Program {
   pub fn new() -> Program {
     Program {
       pointer: 0,
-      variables: Vec::new(),
-      statements: Vec::new()
+      variables: Environment::new(),
+      statements: Arc::from([]),
+      source: Arc::from(""),
+      functions: HashMap::new(),
+      flow: None,
+      call_depth: 0,
+      max_call_depth: MAX_CALL_DEPTH,
+      env_writable: false,
+      env_vars: HashMap::new()
     }
   }
 }
 
-pub fn run(value: Vec<(usize, String)>) {
-  let mut program: Program = Program::new();
-  let lines: Vec<(usize, String)> = value.iter().map(|(a,b)| (*a, b.to_string())).collect();
-  program.statements = match tokenizer(&lines) {
-    Ok(statements) => statements,
-    Err(e) => { e.exit(); }
-  };
+/// An embeddable woojin interpreter.
+///
+/// Nothing in here terminates the host process: failures come back as a [`WoojinError`],
+/// and `yee <code>` comes back as a [`WoojinErrorKind::Exit`] carrying the requested code.
+/// Each interpreter keeps its own variables, so several of them can run at once on different threads.
+pub struct Interpreter {
+  program: Program,
+  file: Option<String>
+}
 
-  run_program(&mut program);
+impl Default for Interpreter {
+  fn default() -> Self {
+    Interpreter::new()
+  }
 }
 
-pub(crate) fn run_program(program: &mut Program) {
-  for stmt in &program.statements {
-    match exec(stmt) {
-      Ok(_) => {},
-      Err(e) => { e.exit(); }
+impl Interpreter {
+  pub fn new() -> Interpreter {
+    let mut interpreter: Interpreter = Interpreter { program: Program::new(), file: None };
+    interpreter.set_args(Vec::new());
+    interpreter
+  }
+
+  /// Sets the arguments the program sees as `$args`, an array of strings. It is empty by default.
+  pub fn set_args(&mut self, args: Vec<String>) {
+    self.program.variables.define("args", WoojinValue::Array(args.into_iter().map(WoojinValue::String).collect()));
+  }
+
+  /// Lets `setenv` set environment variables. It is off by default. The variables are only
+  /// set for this interpreter, where `env` reads them before those of the process: the
+  /// environment of the process is never changed, since other threads may be reading it.
+  pub fn allow_env_write(&mut self, allow: bool) {
+    self.program.env_writable = allow;
+  }
+
+  /// Sets how deep function calls may nest before the program stops with a `StackOverflow`
+  /// error. It is 1000 by default. Programs run on a thread of their own whose stack grows
+  /// with the limit, so the limit doesn't depend on the stack of the thread calling [`Interpreter::run`].
+  pub fn set_max_call_depth(&mut self, depth: usize) {
+    self.program.max_call_depth = depth;
+  }
+
+  /// Runs woojin source code and returns the value of the last executed statement.
+  pub fn eval_str(&mut self, src: &str) -> Result<WoojinValue, WoojinError> {
+    self.load_str(src).map_err(|mut errors| errors.remove(0))?;
+    self.run()
+  }
+
+  /// Reads a woojin file and runs it like [`Interpreter::eval_str`].
+  pub fn run_file(&mut self, path: impl AsRef<Path>) -> Result<WoojinValue, WoojinError> {
+    self.load_file(path).map_err(|mut errors| errors.remove(0))?;
+    self.run()
+  }
+
+  /// Parses woojin source code for [`Interpreter::run`] without running it.
+  /// Every parse error in the code is returned, not just the first one.
+  pub fn load_str(&mut self, src: &str) -> Result<(), Vec<WoojinError>> {
+    self.program.source = Arc::from(src);
+    self.file = None;
+    self.load()
+  }
+
+  /// Reads and parses a woojin file like [`Interpreter::load_str`].
+  pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<(), Vec<WoojinError>> {
+    let path: &Path = path.as_ref();
+    self.program.source = match std::fs::read_to_string(path) {
+      Ok(src) => Arc::from(src),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(vec![WoojinError::new("File Not Found", WoojinErrorKind::FileNotFound).with_file(path.display())]),
+      Err(e) => return Err(vec![WoojinError::new(format!("Failed to read file: {}", e), WoojinErrorKind::FailReadFailure).with_file(path.display())])
+    };
+    self.file = Some(path.display().to_string());
+    self.load()
+  }
+
+  fn load(&mut self) -> Result<(), Vec<WoojinError>> {
+    match parse(&self.program.source) {
+      Ok(statements) => { self.program.statements = Arc::from(statements); Ok(()) },
+      Err(errors) => Err(errors.into_iter().map(|e| self.report(e)).collect())
     }
   }
-}
 
-pub(crate) fn check_calc(calc: Calc) -> WoojinResult<WoojinValue> {
-  match calc {
-    Calc::Add(a, b) => Ok(check_calc(*a)?.add(&check_calc(*b)?)?),
-    Calc::Sub(a, b) => Ok(check_calc(*a)?.sub(&check_calc(*b)?)?),
-    Calc::Mul(a, b) => Ok(check_calc(*a)?.mul(&check_calc(*b)?)?),
-    Calc::Div(a, b) => Ok(check_calc(*a)?.div(&check_calc(*b)?)?),
-    Calc::Equal(a, b) => Ok(check_calc(*a)?.equal(&check_calc(*b)?)?),
-    Calc::NotEqual(a, b) => Ok(check_calc(*a)?.not_equal(&check_calc(*b)?)?),
-    Calc::GreaterThan(a, b) => Ok(check_calc(*a)?.biggerthen(&check_calc(*b)?)?),
-    Calc::LessThan(a, b) => Ok(check_calc(*a)?.smallerthen(&check_calc(*b)?)?),
-    Calc::GreaterThanOrEqual(a, b) => Ok(check_calc(*a)?.biggerthen_equal(&check_calc(*b)?)?),
-    Calc::LessThanOrEqual(a, b) => Ok(check_calc(*a)?.smallerthen_equal(&check_calc(*b)?)?),
-    Calc::Value(val) => return Ok(val.clone()),
+  /// Runs the code loaded last and returns the value of the last executed statement.
+  /// It can be run again: the variables and functions of the earlier runs are kept,
+  /// so a second run of `let a = 1` fails because `a` is already declared.
+  pub fn run(&mut self) -> Result<WoojinValue, WoojinError> {
+    let program: &mut Program = &mut self.program;
+    let stack: usize = program.max_call_depth.saturating_mul(CALL_STACK).saturating_add(RUN_STACK);
+    // Function calls recurse on the stack, so the program gets a thread with a stack sized for them.
+    let result: WoojinResult<WoojinValue> = std::thread::scope(|scope| {
+      match std::thread::Builder::new().stack_size(stack).spawn_scoped(scope, || program.run()) {
+        Ok(thread) => thread.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+        Err(e) => Err(WoojinError::new(format!("Failed to start the thread the program runs on: {}", e), WoojinErrorKind::Unknown))
+      }
+    });
+    result.map_err(|e| self.report(e))
+  }
+
+  /// Adds the source line and file name to an error from the loaded code.
+  /// Errors from inside functions already quote the code the function was written in.
+  fn report(&self, e: WoojinError) -> WoojinError {
+    let e: WoojinError = e.with_source(&self.program.source);
+    match &self.file {
+      Some(file) => e.with_file(file),
+      None => e
+    }
   }
 }
 
-pub(crate) fn exec(stmt: &Statements) -> Result<WoojinValue, crate::error::WoojinError> {
-  match stmt {
-    Statements::Yee { code } => { std::process::exit(*code); },
-    Statements::Roar { value } => { WoojinError::new(&value.to_print(), error::WoojinErrorKind::Roar); },
-    Statements::Print { values } => {
-      for (i, value) in values.iter().enumerate() {
-        print!("{}", exec(value)?.to_print());
-        if i != values.len() - 1 { print!(" "); }
-      }
-      std::io::stdout().flush().unwrap();
-    },
-    Statements::Println { values } => {
-      for (i, value) in values.iter().enumerate() {
-        print!("{}", exec(value)?.to_print());
-        if i != values.len() - 1 { print!(" "); } else { print!("\n"); }
-      }
-      std::io::stdout().flush().unwrap();
-    },
-    Statements::Input { question } => {
-      let mut input: String = String::new();
-      if let Err(e) = exec(&Statements::Print{ values: vec![question.clone()] }) {
-        e.exit();
-      }
-      std::io::stdin().read_line(&mut input).unwrap();
-      return Ok(WoojinValue::String(input.trim().to_string()));
-    },
-    Statements::Sleep { value } => {
-      match exec(&**value)? {
-        WoojinValue::Int(num) => std::thread::sleep(std::time::Duration::from_millis(num as u64)),
-        _ => WoojinError::new("The param of the sleep function must be an integer", error::WoojinErrorKind::TypeMismatch).exit()
+impl Program {
+  pub(crate) fn run(&mut self) -> WoojinResult<WoojinValue> {
+    let statements: Arc<[Stmt]> = self.statements.clone();
+    let mut last: WoojinValue = WoojinValue::Unit;
+    for stmt in statements.iter() {
+      last = self.exec(stmt)?;
+      if let Some(flow) = self.flow.take() { return Err(flow_error(flow).with_span(stmt.span)); }
+    }
+    Ok(last)
+  }
+
+  /// Runs a block body in its own scope, dropping the block's bindings afterwards.
+  pub(crate) fn exec_block(&mut self, stmts: &[Stmt]) -> WoojinResult<WoojinValue> {
+    self.variables.push_scope();
+    let mut result: WoojinResult<WoojinValue> = Ok(WoojinValue::Unit);
+    for stmt in stmts {
+      result = self.exec(stmt);
+      if result.is_err() || self.flow.is_some() { break; }
+    }
+    self.variables.pop_scope();
+    result
+  }
+
+  /// Consumes the flow left behind by a loop body. Returns `true` if the loop has to stop.
+  fn loop_flow(&mut self) -> bool {
+    match self.flow {
+      Some(Flow::Break) => { self.flow = None; true },
+      Some(Flow::Continue) => { self.flow = None; false },
+      Some(Flow::Return(_)) => true,
+      None => false
+    }
+  }
+
+  /// Functions that are always available unless a user-defined function takes the name.
+  fn call_builtin(&mut self, name: &str, args: &[Expr]) -> WoojinResult<WoojinValue> {
+    let mut values: Vec<WoojinValue> = Vec::new();
+    for arg in args { values.push(self.check_calc(arg)?); }
+    match (name, values.as_slice()) {
+      ("len", [WoojinValue::Array(values)]) => Ok(WoojinValue::Int(values.len() as i32)),
+      ("len", [WoojinValue::String(s)]) => Ok(WoojinValue::Int(s.chars().count() as i32)),
+      ("len", [value]) => Err(WoojinError::new(format!("Cannot take the length of {}", value.kind()), WoojinErrorKind::TypeMismatch)),
+      ("len", _) => Err(WoojinError::new(format!("Function len takes 1 argument(s) but {} were given", values.len()), WoojinErrorKind::ArgumentMismatch)),
+      ("env", [name]) => self.read_env(name.clone()).map_err(|e| e.with_span(args[0].span)),
+      _ => Err(WoojinError::new(format!("Function {} is not declared", name), WoojinErrorKind::UndeclaredFunction).with_help("functions have to be declared with `fn` before they are called"))
+    }
+  }
+
+  pub(crate) fn call(&mut self, name: &str, args: &[Expr]) -> WoojinResult<WoojinValue> {
+    let function: Arc<Function> = match self.functions.get(name) {
+      Some(function) => function.clone(),
+      None => return self.call_builtin(name, args)
+    };
+    let values: Vec<WoojinValue> = self.call_args(name, &function, args)?;
+    self.call_function(&function, values)
+  }
+
+  /// Evaluates the arguments of a call and checks them against the parameters of `function`.
+  fn call_args(&mut self, name: &str, function: &Function, args: &[Expr]) -> WoojinResult<Vec<WoojinValue>> {
+    if args.len() != function.params.len() {
+      return Err(WoojinError::new(format!("Function {} takes {} argument(s) but {} were given", name, function.params.len(), args.len()), WoojinErrorKind::ArgumentMismatch));
+    }
+    let mut values: Vec<WoojinValue> = Vec::new();
+    for ((param, kind), arg) in function.params.iter().zip(args) {
+      let value: WoojinValue = self.check_calc(arg)?;
+      match value.coerce(*kind) {
+        Some(value) => values.push(value),
+        None => return Err(WoojinError::new(format!("Parameter {} of function {} must be {}, not {}", param, name, kind, value.kind()), WoojinErrorKind::TypeMismatch))
       }
-    },
-    Statements::Assignment { name, value } => {
-      println!("{:?}!", value);
-      let value: WoojinValue = exec(value)?;
-      variable::change_var(name.as_str(), &value)?;
-      return Ok(value);
-    },
-    Statements::Let { name, stmt, kind, option } => { 
-      let value: WoojinValue = exec(stmt)?;
-      if !value.type_eq(*kind) { return Err(WoojinError::new("The type of the value and the type of the variable are different", error::WoojinErrorKind::TypeMismatch)); }
-      variable::dec_var(name.as_str(), &value, option)?;
-    },
-    // Statements::If { condition: _, body: _ } => {}, 
-    Statements::Value { value } => {
-      return Ok(value.value().clone())
-    },
-    Statements::If { condition, stmt, else_stmt } => {
-      match exec(condition)? {
-        WoojinValue::Bool(b) => {
-          for s in if b { stmt } else { else_stmt } { exec(s)?; }
-        },
-        _ => { return Err(WoojinError::new("The condition of the if statement must be a boolean", error::WoojinErrorKind::TypeMismatch)); }
+    }
+    if self.call_depth >= self.max_call_depth {
+      return Err(WoojinError::new(format!("Function calls nested deeper than {}", self.max_call_depth), WoojinErrorKind::StackOverflow)
+        .with_help("make sure the recursion stops, or raise the limit with `Interpreter::set_max_call_depth`"));
+    }
+    Ok(values)
+  }
+
+  /// Runs the body of `function` in a frame of its own, with its parameters bound to `values`.
+  fn call_function(&mut self, function: &Function, values: Vec<WoojinValue>) -> WoojinResult<WoojinValue> {
+    self.call_depth += 1;
+    let saved: Vec<variable::Scope> = self.variables.enter_frame();
+    let mut result: WoojinResult<WoojinValue> = Ok(WoojinValue::Unit);
+    for ((param, _), value) in function.params.iter().zip(values) {
+      result = self.variables.dec_var(param, &value, &VariableOption::new(None, None)).map(|_| WoojinValue::Unit);
+      if result.is_err() { break; }
+    }
+    if result.is_ok() { result = self.exec_block(&function.body); }
+    self.variables.leave_frame(saved);
+    self.call_depth -= 1;
+    // The function may have been loaded before the code that calls it.
+    result.map_err(|e| e.with_source(&function.source))?;
+
+    match self.flow.take() {
+      Some(Flow::Return(value)) => Ok(value),
+      Some(flow) => Err(flow_error(flow)),
+      None => Ok(WoojinValue::Unit)
+    }
+  }
+
+  /// Evaluates an operand of a logical operator, which has to be a boolean.
+  fn check_bool(&mut self, calc: &Expr, op: &str) -> WoojinResult<bool> {
+    match self.check_calc(calc)? {
+      WoojinValue::Bool(b) => Ok(b),
+      value => Err(WoojinError::new(format!("The operands of {} must be booleans, not {}", op, value.kind()), WoojinErrorKind::TypeMismatch).with_span(calc.span))
+    }
+  }
+
+  pub(crate) fn check_condition(&mut self, condition: &Stmt, name: &str) -> WoojinResult<bool> {
+    match self.exec(condition)? {
+      WoojinValue::Bool(b) => Ok(b),
+      _ => Err(WoojinError::new(format!("The condition of the {} statement must be a boolean", name), WoojinErrorKind::TypeMismatch))
+    }
+  }
+
+  pub(crate) fn resolve(&self, value: &WoojinValue) -> WoojinResult<WoojinValue> {
+    match value {
+      WoojinValue::Var(name) => Ok(self.variables.get_var(name.as_str())?.value),
+      _ => Ok(value.clone()),
+    }
+  }
+
+  /// Evaluates an expression, pointing any error at the innermost node that caused it.
+  pub(crate) fn check_calc(&mut self, calc: &Expr) -> WoojinResult<WoojinValue> {
+    self.eval_calc(&calc.node).map_err(|e| e.with_span(calc.span))
+  }
+
+  /// Evaluates both operands, left first, and applies `op` to them.
+  fn check_binary(&mut self, a: &Expr, b: &Expr, op: fn(&WoojinValue, &WoojinValue) -> WoojinResult<WoojinValue>) -> WoojinResult<WoojinValue> {
+    let a: WoojinValue = self.check_calc(a)?;
+    let b: WoojinValue = self.check_calc(b)?;
+    op(&a, &b)
+  }
+
+  fn check_unary(&mut self, a: &Expr, op: fn(&WoojinValue) -> WoojinResult<WoojinValue>) -> WoojinResult<WoojinValue> {
+    op(&self.check_calc(a)?)
+  }
+
+  /// Applies the operators of a chain from left to right. A chain of `and` or of `or` stops
+  /// as soon as an operand decides the result, without evaluating the rest.
+  fn check_chain(&mut self, first: &Expr, links: &[(BinaryOp, Expr)]) -> WoojinResult<WoojinValue> {
+    let mut value: WoojinValue = match links.first() {
+      Some((BinaryOp::And, _)) => WoojinValue::Bool(self.check_bool(first, "and")?),
+      Some((BinaryOp::Or, _)) => WoojinValue::Bool(self.check_bool(first, "or")?),
+      _ => self.check_calc(first)?
+    };
+    for (op, operand) in links {
+      value = match op.function() {
+        // An error is pointed at the part of the chain evaluated so far.
+        Some(function) => function(&value, &self.check_calc(operand)?).map_err(|e| e.with_span(first.span.to(operand.span)))?,
+        // `and` stops at the first false, `or` at the first true.
+        None if value == WoojinValue::Bool(*op == BinaryOp::Or) => return Ok(value),
+        None => WoojinValue::Bool(self.check_bool(operand, if *op == BinaryOp::And { "and" } else { "or" })?)
+      };
+    }
+    Ok(value)
+  }
+
+  fn check_not(&mut self, a: &Expr) -> WoojinResult<WoojinValue> {
+    Ok(WoojinValue::Bool(!self.check_bool(a, "not")?))
+  }
+
+  fn check_array(&mut self, items: &[Expr]) -> WoojinResult<WoojinValue> {
+    let mut values: Vec<WoojinValue> = Vec::new();
+    for item in items { values.push(self.check_calc(item)?); }
+    Ok(WoojinValue::Array(values))
+  }
+
+  fn check_field(&mut self, a: &Expr, name: &str) -> WoojinResult<WoojinValue> {
+    self.check_calc(a)?.field(name)
+  }
+
+  // Like `exec_statement`, every arm is a single call to keep the stack frame small.
+  fn eval_calc(&mut self, calc: &Calc) -> WoojinResult<WoojinValue> {
+    match calc {
+      Calc::Chain(first, links) => self.check_chain(first, links),
+      Calc::Pow(a, b) => self.check_binary(a, b, ValueCalc::pow),
+      Calc::Not(a) => self.check_not(a),
+      Calc::Neg(a) => self.check_unary(a, ValueCalc::neg),
+      Calc::Call(name, args) => self.call(name, args),
+      Calc::Array(items) => self.check_array(items),
+      Calc::Index(a, i) => self.check_binary(a, i, ValueCalc::index),
+      Calc::Field(a, name) => self.check_field(a, name),
+      Calc::Value(val) => self.resolve(val),
+    }
+  }
+
+  fn print_values(&mut self, values: &[Stmt], newline: bool) -> WoojinResult<WoojinValue> {
+    for (i, value) in values.iter().enumerate() {
+      if i != 0 { print!(" "); }
+      print!("{}", self.exec(value)?.to_print());
+    }
+    if newline { println!(); }
+    std::io::stdout().flush().unwrap();
+    Ok(WoojinValue::Unit)
+  }
+
+  fn exec_for(&mut self, name: &str, iter: &Iterable, body: &[Stmt]) -> WoojinResult<WoojinValue> {
+    match iter {
+      Iterable::Range { start, end } => match (self.exec(start)?, self.exec(end)?) {
+        (WoojinValue::Int(start), WoojinValue::Int(end)) => self.exec_loop(name, (start..end).map(WoojinValue::Int), body),
+        // A range with a long bound counts in longs.
+        (start, end) => match (long_bound(&start), long_bound(&end)) {
+          (Some(start), Some(end)) => self.exec_loop(name, (start..end).map(WoojinValue::Long), body),
+          _ => Err(WoojinError::new("The bounds of a range must be integers", WoojinErrorKind::TypeMismatch))
+        }
+      },
+      Iterable::Value(value) => match self.exec(value)? {
+        WoojinValue::Array(values) => self.exec_loop(name, values.into_iter(), body),
+        value => Err(WoojinError::new(format!("Cannot iterate over {}", value.kind()), WoojinErrorKind::TypeMismatch))
       }
+    }
+  }
+
+  /// Runs the body of a `for` loop once for every value, which is only produced when its turn comes.
+  fn exec_loop(&mut self, name: &str, values: impl Iterator<Item = WoojinValue>, body: &[Stmt]) -> WoojinResult<WoojinValue> {
+    for value in values {
+      self.variables.push_scope();
+      let result: WoojinResult<WoojinValue> = self.variables.dec_var(name, &value, &VariableOption::new(None, None))
+        .and_then(|_| self.exec_block(body));
+      self.variables.pop_scope();
+      result?;
+      if self.loop_flow() { break; }
+    }
+    Ok(WoojinValue::Unit)
+  }
+
+  /// Raises the error of `roar`. It stops the program with `code`, or 1 if no code is given.
+  fn roar(&mut self, value: &Stmt, code: Option<&Stmt>) -> WoojinResult<WoojinValue> {
+    let message: String = self.exec(value)?.to_print();
+    let error: WoojinError = WoojinError::new(message, WoojinErrorKind::Roar);
+    let code: &Stmt = match code {
+      Some(code) => code,
+      None => return Err(error)
+    };
+    match self.exec(code)? {
+      WoojinValue::Int(status) if status != 0 => Err(error.with_status(status)),
+      value => Err(WoojinError::new(format!("The exit code of roar must be a non-zero int, not {}", value.to_print()), WoojinErrorKind::TypeMismatch).with_span(code.span))
+    }
+  }
+
+  /// `sleep <milliseconds>`.
+  fn sleep(&mut self, value: &Stmt) -> WoojinResult<WoojinValue> {
+    let millis: i64 = match self.exec(value)? {
+      WoojinValue::Int(num) => num as i64,
+      WoojinValue::Long(num) => num,
+      _ => return Err(WoojinError::new("The param of the sleep function must be an integer", WoojinErrorKind::TypeMismatch))
+    };
+    match u64::try_from(millis) {
+      Ok(millis) => std::thread::sleep(std::time::Duration::from_millis(millis)),
+      Err(_) => return Err(WoojinError::new(format!("Cannot sleep for a negative time ({} ms)", millis), WoojinErrorKind::TypeMismatch))
+    }
+    Ok(WoojinValue::Unit)
+  }
+
+  /// `$name = value`.
+  fn assign(&mut self, name: &str, value: &Stmt) -> WoojinResult<WoojinValue> {
+    let value: WoojinValue = self.exec(value)?;
+    self.variables.change_var(name, &value)?;
+    Ok(value)
+  }
+
+  /// `let name: kind = value`.
+  fn declare(&mut self, name: &str, value: &Stmt, kind: WoojinValueKind, option: &VariableOption) -> WoojinResult<WoojinValue> {
+    let value: WoojinValue = match self.exec(value)?.coerce(kind) {
+      Some(value) => value,
+      None => return Err(WoojinError::new("The type of the value and the type of the variable are different", WoojinErrorKind::TypeMismatch))
+    };
+    self.variables.dec_var(name, &value, option)?;
+    Ok(WoojinValue::Unit)
+  }
+
+  fn exec_if(&mut self, condition: &Stmt, stmt: &[Stmt], else_stmt: &[Stmt]) -> WoojinResult<WoojinValue> {
+    let b: bool = self.check_condition(condition, "if")?;
+    self.exec_block(if b { stmt } else { else_stmt })?;
+    Ok(WoojinValue::Unit)
+  }
+
+  fn exec_while(&mut self, condition: &Stmt, body: &[Stmt]) -> WoojinResult<WoojinValue> {
+    while self.check_condition(condition, "while")? {
+      self.exec_block(body)?;
+      if self.loop_flow() { break; }
+    }
+    Ok(WoojinValue::Unit)
+  }
+
+  fn exec_return(&mut self, value: Option<&Stmt>) -> WoojinResult<WoojinValue> {
+    let value: WoojinValue = match value {
+      Some(value) => self.exec(value)?,
+      None => WoojinValue::Unit
+    };
+    self.flow = Some(Flow::Return(value));
+    Ok(WoojinValue::Unit)
+  }
+
+  /// Asks `question` and reads a line from standard input.
+  fn read_input(&mut self, question: &Stmt) -> WoojinResult<WoojinValue> {
+    let mut input: String = String::new();
+    self.print_values(std::slice::from_ref(question), false)?;
+    if let Err(e) = std::io::stdin().read_line(&mut input) {
+      return Err(WoojinError::new(format!("Failed to read input: {}", e), WoojinErrorKind::FailReadFailure));
+    }
+    Ok(WoojinValue::String(input.trim().to_string()))
+  }
+
+  /// `$name[i][j] = value`.
+  fn assign_index(&mut self, name: &str, indices: &[Expr], value: &Stmt) -> WoojinResult<WoojinValue> {
+    let mut positions: Vec<WoojinValue> = Vec::new();
+    for index in indices { positions.push(self.check_calc(index)?); }
+    let value: WoojinValue = self.exec(value)?;
+    let mut array: WoojinValue = self.variables.get_var(name)?.value;
+    set_index(&mut array, &positions, value.clone())?;
+    self.variables.change_var(name, &array)?;
+    Ok(value)
+  }
+
+  fn set_env(&mut self, name: &Stmt, value: &Stmt) -> WoojinResult<WoojinValue> {
+    if !self.env_writable {
+      return Err(WoojinError::new("Setting environment variables is not allowed", WoojinErrorKind::PermissionDenied)
+        .with_help("run woojin with --allow-env to let the program set them"));
+    }
+    let name: String = env_name(self.exec(name)?).map_err(|e| e.with_span(name.span))?;
+    let value: String = match self.exec(value)? {
+      WoojinValue::String(value) if !value.contains('\0') => value,
+      WoojinValue::String(_) => return Err(WoojinError::new("An environment variable can't contain a NUL character", WoojinErrorKind::TypeMismatch).with_span(value.span)),
+      found => return Err(WoojinError::new(format!("The value of an environment variable must be a string, not {}", found.kind()), WoojinErrorKind::TypeMismatch).with_span(value.span))
+    };
+    self.env_vars.insert(name, value);
+    Ok(WoojinValue::Unit)
+  }
+
+  /// The value of the environment variable `name`, as read by `env`.
+  fn read_env(&self, name: WoojinValue) -> WoojinResult<WoojinValue> {
+    let name: String = env_name(name)?;
+    if let Some(value) = self.env_vars.get(&name) { return Ok(WoojinValue::String(value.clone())); }
+    // An unset variable reads as an empty string, like in a shell.
+    Ok(WoojinValue::String(std::env::var_os(&name).map(|value| value.to_string_lossy().into_owned()).unwrap_or_default()))
+  }
+
+  /// Runs `body`, and `handler` if `body` fails with an error. `yee` is not an error and can't be caught.
+  fn exec_try(&mut self, body: &[Stmt], name: Option<&str>, handler: &[Stmt]) -> WoojinResult<WoojinValue> {
+    let error: WoojinError = match self.exec_block(body) {
+      Err(e) if e.exit_code().is_none() => e,
+      result => return result.map(|_| WoojinValue::Unit)
+    };
+    self.variables.push_scope();
+    let caught: WoojinValue = WoojinValue::Error { kind: error.kind, message: error.details };
+    let result: WoojinResult<WoojinValue> = match name {
+      Some(name) => self.variables.dec_var(name, &caught, &VariableOption::new(None, None)),
+      None => Ok(())
+    }.and_then(|_| self.exec_block(handler));
+    self.variables.pop_scope();
+    result.map(|_| WoojinValue::Unit)
+  }
+
+  /// Runs a statement, pointing any error at the innermost node that caused it.
+  pub(crate) fn exec(&mut self, stmt: &Stmt) -> WoojinResult<WoojinValue> {
+    self.exec_statement(&stmt.node).map_err(|e| e.with_span(stmt.span))
+  }
+
+  // Every arm hands its work to a method of its own. The stack frame of a function holds the
+  // locals of all of its arms at once, and `exec_statement` is part of every level of nesting
+  // in a program, so keeping it small lets programs nest deeper on the same stack.
+  fn exec_statement(&mut self, stmt: &Statements) -> WoojinResult<WoojinValue> {
+    match stmt {
+      Statements::Yee { code } => Err(WoojinError::new(format!("yee {}", code), WoojinErrorKind::Exit(*code))),
+      Statements::Roar { value, code } => self.roar(value, code.as_deref()),
+      Statements::Print { values } => self.print_values(values, false),
+      Statements::Println { values } => self.print_values(values, true),
+      Statements::Input { question } => self.read_input(question),
+      Statements::SetEnv { name, value } => self.set_env(name, value),
+      Statements::Sleep { value } => self.sleep(value),
+      Statements::Assignment { name, value } => self.assign(name, value),
+      Statements::IndexAssignment { name, indices, value } => self.assign_index(name, indices, value),
+      Statements::Let { name, stmt, kind, option } => self.declare(name, stmt, *kind, option),
+      Statements::Value { value } => self.resolve(value),
+      Statements::If { condition, stmt, else_stmt } => self.exec_if(condition, stmt, else_stmt),
+      Statements::While { condition, body } => self.exec_while(condition, body),
+      Statements::For { name, iter, body } => self.exec_for(name, iter, body),
+      Statements::Function { name, params, body } => {
+        self.functions.insert(name.clone(), Arc::new(Function { params: params.clone(), body: body.clone(), source: self.source.clone() }));
+        Ok(WoojinValue::Unit)
+      },
+      Statements::Try { body, name, handler } => self.exec_try(body, name.as_deref(), handler),
+      Statements::Return { value } => self.exec_return(value.as_deref()),
+      Statements::Break => { self.flow = Some(Flow::Break); Ok(WoojinValue::Unit) },
+      Statements::Continue => { self.flow = Some(Flow::Continue); Ok(WoojinValue::Unit) },
+      Statements::Calc(calc) => self.check_calc(calc),
+      Statements::Comment(_) => Ok(WoojinValue::Unit)
+    }
+  }
+}
+
+/// Replaces the element at `indices` inside nested arrays.
+fn set_index(target: &mut WoojinValue, indices: &[WoojinValue], value: WoojinValue) -> WoojinResult<()> {
+  let (index, rest) = match indices.split_first() {
+    Some(split) => split,
+    None => { *target = value; return Ok(()); }
+  };
+  match target {
+    WoojinValue::Array(values) => {
+      let i: usize = calc::array_index(index, values.len())?;
+      set_index(&mut values[i], rest, value)
     },
-    Statements::Calc(calc) => { return check_calc(calc.clone()); },
-    Statements::Comment(_) => {}
+    _ => Err(WoojinError::new(format!("Cannot index into {}", target.kind()), WoojinErrorKind::TypeMismatch))
+  }
+}
+
+/// A bound of a range as a long, if it is an integer.
+fn long_bound(value: &WoojinValue) -> Option<i64> {
+  match value {
+    WoojinValue::Int(n) => Some(*n as i64),
+    WoojinValue::Long(n) => Some(*n),
+    _ => None
+  }
+}
+
+fn flow_error(flow: Flow) -> WoojinError {
+  match flow {
+    Flow::Break => WoojinError::new("break can only be used inside a loop", WoojinErrorKind::LoopControlOutsideLoop).with_help("move it into the body of a `while` or `for` loop"),
+    Flow::Continue => WoojinError::new("continue can only be used inside a loop", WoojinErrorKind::LoopControlOutsideLoop).with_help("move it into the body of a `while` or `for` loop"),
+    Flow::Return(_) => WoojinError::new("return can only be used inside a function", WoojinErrorKind::ReturnOutsideFunction).with_help("use `yee <code>` to stop the program instead"),
   }
-  Ok(WoojinValue::Unit)
-}
\ No newline at end of file
+}
+
+/// Checks the name given to `env` or `setenv`.
+fn env_name(name: WoojinValue) -> WoojinResult<String> {
+  match name {
+    WoojinValue::String(name) if !name.is_empty() && !name.contains(['=', '\0']) => Ok(name),
+    WoojinValue::String(name) => Err(WoojinError::new(format!("{:?} is not a valid environment variable name", name), WoojinErrorKind::TypeMismatch)),
+    name => Err(WoojinError::new(format!("The name of an environment variable must be a string, not {}", name.kind()), WoojinErrorKind::TypeMismatch))
+  }
+}
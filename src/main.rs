@@ -1,46 +1,76 @@
-use std::{fs::File, io::{BufReader, BufRead}};
+mod cli;
+mod repl;
+
+use std::io::Read;
+
+use cli::{Command, Source};
 use woojin::{
   error::{WoojinError, WoojinErrorKind},
+  Interpreter,
 };
 
-const INDENT: usize = 2;
-
 fn main() {
-  let args: Vec<String> = std::env::args().collect::<Vec<String>>();
-  if args.len() < 2 {
-    WoojinError::new(
-      "Give Me File!\nUsage: woojin [file]",
-      WoojinErrorKind::FileNotFound,
-    )
-    .exit();
-  }
-  let path: &String = &args[1];
-  if !path.ends_with(".wj")&&!path.ends_with(".woojin") {
-    WoojinError::new(
-      "I don't think it's woojin file(.wj, .woojin)",
-      WoojinErrorKind::UnsupportedExtension,
-    )
-    .exit();
-  }
-  let file: File = match File::open(path) {
-    Ok(file) => file,
-    Err(_) => WoojinError::new(
-      "File Not Found",
-      WoojinErrorKind::FileNotFound
-    ).exit(),
-  };
-  let reader: BufReader<File> = BufReader::new(file);
-  let mut lines: Vec<(usize, String)> = Vec::new();
-  for line in reader.lines() {
-    if let Ok(line) = line {
-      if line.is_empty() { continue; }
-      let line: Vec<&str> = line.split(" ".repeat(INDENT).as_str()).collect::<Vec<&str>>();
-      lines.push((line.len()-1, line[line.len()-1].to_string()))
+  let args: Vec<String> = std::env::args().skip(1).collect::<Vec<String>>();
+  match cli::parse(&args) {
+    Ok(Command::Repl) => repl::run(),
+    Ok(Command::Run { source, args, any_extension, allow_env }) => run(source, args, any_extension, allow_env),
+    Ok(Command::Explain(code)) => explain(&code),
+    Ok(Command::Help) => println!("woojin {}\n\n{}", env!("CARGO_PKG_VERSION"), cli::USAGE),
+    Ok(Command::Version) => println!("woojin {}", env!("CARGO_PKG_VERSION")),
+    Err(message) => {
+      eprintln!("error: {}\n\n{}", message, cli::USAGE);
+      std::process::exit(2);
     }
   }
-  if lines[lines.len()-1].0 != 0||!lines[lines.len()-1].1.starts_with("yee") {
-    lines.push((0, "yee 0".to_string()));
+}
+
+/// Runs a program and exits with its status: 0 when it finishes, the code given to `yee`
+/// or `roar`, and 1 for any other error.
+fn run(source: Source, args: Vec<String>, any_extension: bool, allow_env: bool) {
+  let mut interpreter: Interpreter = Interpreter::new();
+  interpreter.set_args(args);
+  interpreter.allow_env_write(allow_env);
+  // Programs that don't come from a file are named after where they came from in reports.
+  let (name, loaded): (String, Result<(), Vec<WoojinError>>) = match source {
+    Source::File(path) => {
+      if !any_extension && !path.ends_with(".wj") && !path.ends_with(".woojin") {
+        WoojinError::new(
+          "I don't think it's woojin file(.wj, .woojin)",
+          WoojinErrorKind::UnsupportedExtension,
+        )
+        .with_file(&path)
+        .with_help("woojin files end in `.wj` or `.woojin`; pass --any-extension to run it anyway")
+        .exit();
+      }
+      let loaded = interpreter.load_file(&path);
+      (path, loaded)
+    },
+    Source::Stdin => {
+      let mut src: String = String::new();
+      if let Err(e) = std::io::stdin().read_to_string(&mut src) {
+        WoojinError::new(format!("Failed to read the program from standard input: {}", e), WoojinErrorKind::FailReadFailure).exit();
+      }
+      ("<stdin>".to_string(), interpreter.load_str(&src))
+    },
+    Source::Inline(code) => ("<-e>".to_string(), interpreter.load_str(&code)),
+  };
+  if let Err(errors) = loaded {
+    let count: usize = errors.len();
+    for e in errors { println!("{}\n", e.with_file(&name)); }
+    println!("error: could not run {} due to {} previous error{}", name, count, if count == 1 { "" } else { "s" });
+    std::process::exit(1);
   }
-  woojin::run(lines);
+  if let Err(e) = interpreter.run() { e.with_file(&name).exit(); }
 }
 
+fn explain(code: &str) -> ! {
+  match WoojinErrorKind::from_code(code).and_then(|kind| Some((kind, kind.code()?, kind.explain()?))) {
+    Some((kind, code, text)) => {
+      println!("WJ{} ({:?})\n\n{}", code, kind, text.trim_end());
+      std::process::exit(0);
+    },
+    None => WoojinError::new(format!("{:?} is not a woojin error code", code), WoojinErrorKind::Unknown)
+      .with_help("error codes look like `WJ13`")
+      .exit()
+  }
+}
@@ -1,300 +1,401 @@
 use std::str::FromStr;
-use regex::Regex;
 
 use crate::{
-  ast::Statements,
-  NomResult, types::{WoojinValue, parse::parse_value, WoojinValueKind}, error::WoojinError, variable::VariableOption, exec, calc::{parse_calc, Calc}
-};
-
-use nom::{
-  IResult,
-  branch::{alt},
-  bytes::complete::{
-    tag,
-    take_while_m_n,
-    take_while1, take_while,
-  },
-  character::complete::{char, multispace1, multispace0},
-  sequence::{preceded, terminated},
-  combinator::{map, map_res, opt, value}
+  ast::{Statements, Stmt, Spanned, Iterable},
+  types::WoojinValueKind, error::{WoojinError, WoojinErrorKind, Span}, variable::VariableOption, calc::{parse_calc, parse_index, Calc, Expr},
+  lexer::{lex, Keyword, Token, TokenKind},
 };
 
 pub(crate) type WoojinResult<T> = Result<T, crate::error::WoojinError>;
 
-pub(crate) fn parse_int(input: &str) -> Result<i32, std::num::ParseIntError> {
-  i32::from_str_radix(input, 10)
-}
+const BLOCK_HELP: &str = "indent the lines of the block one level deeper than its header";
 
-pub(crate) fn yee(input: &str) -> NomResult<Statements> {
-  let (input, _): (&str, &str) = tag("yee ")(input)?;
-  let (input, sign): (&str, Option<&str>) = opt(tag("-"))(input)?;
-  let (input, num): (&str, i32) = map_res(take_while_m_n(1, 10, |c: char| c.is_digit(10)), parse_int)(input)?;
-  let num: i32 = if let Some(_) = sign { -num } else { num };
-  Ok((input, Statements::Yee { code: num }))
-}
+/// How deeply blocks and expressions may nest. Parsing and running nested code both recurse,
+/// so without a limit a deep enough program would overflow the stack of the host thread.
+const MAX_NESTING: usize = 32;
 
-pub(crate) fn vec2stmt(values: &Vec<&str>) -> WoojinResult<Vec<Box<Statements>>> {
-  let mut result: Vec<Box<Statements>> = vec![];
-  for value in values {
-    let val: Statements = tokenize_line(value)?;
-    result.push(Box::new(val));
-  };
-  Ok(result)
+/// A cursor over the tokens of a program. It never moves past the final `Eof`.
+pub(crate) struct Parser {
+  tokens: Vec<Token>,
+  pos: usize,
+  /// Errors in statements that were skipped so that parsing could go on.
+  errors: Vec<WoojinError>,
+  /// How many blocks and operators the current token is nested in.
+  depth: usize,
 }
 
-pub(crate) fn split_comma(input: &str) -> WoojinResult<Vec<&str>> {
-  let values: Vec<&str> = if input.trim().contains(",") {
-    let mut in_quotes: bool = false;
-    let mut start: usize = 0;
-    let mut result: Vec<&str> = vec![];
-    let chars: std::iter::Enumerate<std::str::Chars> = input.trim().chars().enumerate();
-    for (i, c) in chars {
-      match c {
-        '"' => in_quotes = !in_quotes,
-        ',' if !in_quotes => {
-          let value: &&str = &input[start..i].trim();
-          result.push(value.to_owned()); // push owned value
-          start = i + 1;
-        }
+impl Parser {
+  fn new(tokens: Vec<Token>) -> Parser {
+    Parser { tokens, pos: 0, errors: Vec::new(), depth: 0 }
+  }
+
+  pub(crate) fn peek(&self) -> &Token {
+    &self.tokens[self.pos]
+  }
+
+  /// The token `n` places after the current one.
+  pub(crate) fn nth(&self, n: usize) -> &Token {
+    &self.tokens[(self.pos + n).min(self.tokens.len() - 1)]
+  }
+
+  pub(crate) fn advance(&mut self) -> Token {
+    let token: Token = self.tokens[self.pos].clone();
+    if self.pos < self.tokens.len() - 1 { self.pos += 1; }
+    token
+  }
+
+  /// Moves past the current token if it is `kind`.
+  pub(crate) fn eat(&mut self, kind: &TokenKind) -> bool {
+    if &self.peek().kind != kind { return false; }
+    self.advance();
+    true
+  }
+
+  /// Moves past the current token, which has to be `kind`.
+  pub(crate) fn expect(&mut self, kind: &TokenKind, context: &str) -> WoojinResult<Span> {
+    if &self.peek().kind == kind { return Ok(self.advance().span); }
+    Err(self.unexpected(&format!("{} {}", kind, context)))
+  }
+
+  /// An error saying that something else was expected where the current token is.
+  pub(crate) fn unexpected(&self, expected: &str) -> WoojinError {
+    let token: &Token = self.peek();
+    WoojinError::new(format!("Expected {}, found {}", expected, token.kind), WoojinErrorKind::ParseError).with_span(token.span)
+  }
+
+  /// The span from `start` to the end of the last token consumed.
+  pub(crate) fn span_from(&self, start: Span) -> Span {
+    start.to(self.tokens[self.pos.saturating_sub(1)].span)
+  }
+
+  /// Goes one level deeper into a block or an expression. Every call is paired with a
+  /// [`Parser::leave`], except when parsing fails.
+  pub(crate) fn enter(&mut self) -> WoojinResult<()> {
+    if self.depth >= MAX_NESTING {
+      return Err(WoojinError::new(format!("Code nested more than {} levels deep", MAX_NESTING), WoojinErrorKind::ParseError)
+        .with_help("split the expression or the block up, for example with variables or functions")
+        .with_span(self.peek().span));
+    }
+    self.depth += 1;
+    Ok(())
+  }
+
+  pub(crate) fn leave(&mut self, levels: usize) {
+    self.depth -= levels;
+  }
+
+  /// Whether the current token ends a statement.
+  fn at_end(&self) -> bool {
+    matches!(self.peek().kind, TokenKind::Newline | TokenKind::Symbol(";"))
+  }
+
+  /// Skips the statement that started at token `start`, along with its block and,
+  /// for `if` and `try`, their `else` and `catch`.
+  fn recover(&mut self, start: usize) {
+    self.pos = start;
+    let head: TokenKind = self.peek().kind.clone();
+    self.skip_line();
+    loop {
+      match (&head, &self.peek().kind) {
+        (TokenKind::Keyword(Keyword::If), TokenKind::Keyword(Keyword::Else)) => self.skip_line(),
+        (TokenKind::Keyword(Keyword::Try), TokenKind::Keyword(Keyword::Catch)) => self.skip_line(),
+        // A line that failed to lex may have been an `if` or a `try`.
+        (TokenKind::Error, TokenKind::Keyword(Keyword::Else | Keyword::Catch)) => self.skip_line(),
+        _ => break
+      }
+    }
+  }
+
+  /// Skips the current line and the block under it.
+  fn skip_line(&mut self) {
+    if self.peek().kind != TokenKind::Indent {
+      while !matches!(self.peek().kind, TokenKind::Newline | TokenKind::Eof) { self.advance(); }
+      self.eat(&TokenKind::Newline);
+    }
+    if self.peek().kind != TokenKind::Indent { return; }
+    let mut depth: usize = 0;
+    loop {
+      match self.advance().kind {
+        TokenKind::Indent => depth += 1,
+        TokenKind::Dedent => { depth -= 1; if depth == 0 { break; } },
+        TokenKind::Eof => break,
         _ => {}
       }
     }
-    let value: &&str = &input[start..].trim();
-    result.push(value.to_owned()); // push owned value
-    result
-  } else {
-    vec![input.trim()]
-  };
-  Ok(values)
+  }
 }
 
-pub(crate) fn print(input: &str) -> WoojinResult<Statements> {
-  let input: &str = match tag::<&str, _, nom::error::Error<&str>>("print ")(input) {
-    Ok((input, _)) => input.trim(),
-    Err(_) => return Err(WoojinError::new("Invalid usage of print", crate::error::WoojinErrorKind::Unknown))
-  };
-  let values: Vec<&str> = split_comma(input)?;
-  Ok(Statements::Print { values: vec2stmt(&values)? })
+/// Parses a whole program. A statement that fails to parse is skipped along with the block
+/// under it, so that all the errors in the program can be reported at once.
+pub(crate) fn parse(src: &str) -> Result<Vec<Stmt>, Vec<WoojinError>> {
+  let (tokens, mut errors): (Vec<Token>, Vec<WoojinError>) = lex(src);
+  let mut parser: Parser = Parser::new(tokens);
+  let statements: Vec<Stmt> = parse_statements(&mut parser);
+  errors.append(&mut parser.errors);
+  // The errors of the lexer and of the parser are each in order, but not with each other.
+  errors.sort_by_key(|e| e.span.map(|span| (span.line, span.column)));
+  if errors.is_empty() { Ok(statements) } else { Err(errors) }
 }
 
-pub(crate) fn println(input: &str) -> WoojinResult<Statements> {
-  let input: &str = match tag::<&str, _, nom::error::Error<&str>>("println ")(input) {
-    Ok((input, _)) => input.trim(),
-    Err(_) => return Err(WoojinError::new("Invalid usage of println", crate::error::WoojinErrorKind::Unknown))
-  };
-  let values: Vec<&str> = split_comma(input)?;
-  Ok(Statements::Println{ values: vec2stmt(&values)? })
+/// Parses statements up to the end of the current block.
+fn parse_statements(p: &mut Parser) -> Vec<Stmt> {
+  let mut result: Vec<Stmt> = vec![];
+  while !matches!(p.peek().kind, TokenKind::Dedent | TokenKind::Eof) {
+    let (start, depth): (usize, usize) = (p.pos, p.depth);
+    if p.peek().kind == TokenKind::Error { p.recover(start); continue; }
+    match parse_statement(p) {
+      Ok(stmt) => result.push(stmt),
+      Err(e) => { p.errors.push(e); p.recover(start); p.depth = depth; }
+    }
+  }
+  result
 }
 
-pub(crate) fn roar(input: &str) -> WoojinResult<Statements> {
-  let input: &str = match tag::<&str, _, nom::error::Error<&str>>("roar ")(input) {
-    Ok((input, _)) => input,
-    Err(_) => return Err(WoojinError::new("Invalid usage of roar", crate::error::WoojinErrorKind::Unknown))
-  };
-  Ok(Statements::Roar { value: test(input)? })
+/// Parses the indented block after a header that ends in `:`.
+/// `missing` is the error to give when there is no block.
+fn parse_block(p: &mut Parser, missing: WoojinError) -> WoojinResult<Vec<Stmt>> {
+  // Like any other line, a header may end in a `;`.
+  p.eat(&TokenKind::Symbol(";"));
+  p.expect(&TokenKind::Newline, "after `:`")?;
+  // The block may be missing only because its first line failed to lex, which was reported already.
+  if p.peek().kind == TokenKind::Error { return Ok(Vec::new()); }
+  if !p.eat(&TokenKind::Indent) { return Err(missing); }
+  p.enter()?;
+  let body: Vec<Stmt> = parse_statements(p);
+  p.leave(1);
+  p.eat(&TokenKind::Dedent);
+  Ok(body)
 }
 
-pub(crate) fn input(i: &str) -> WoojinResult<Statements> {
-  let input: &str = match tag::<&str, _, nom::error::Error<&str>>("input ")(i) {
-    Ok((input, _)) => input,
-    Err(_) => return Err(WoojinError::new("Invalid usage of input", crate::error::WoojinErrorKind::Unknown))
+fn parse_statement(p: &mut Parser) -> WoojinResult<Stmt> {
+  let start: Span = p.peek().span;
+  let node: Statements = match p.peek().kind.clone() {
+    TokenKind::Indent => return Err(WoojinError::new("Unexpected indent", WoojinErrorKind::InvalidIndent)
+      .with_help("only the lines in the block of an `if`, `while`, `for`, `fn`, `try` or `catch` are indented")
+      .with_span(start)),
+    TokenKind::Keyword(Keyword::If) => return parse_if(p),
+    TokenKind::Keyword(Keyword::While) => return parse_while(p),
+    TokenKind::Keyword(Keyword::For) => return parse_for(p),
+    TokenKind::Keyword(Keyword::Fn) => return parse_fn(p),
+    TokenKind::Keyword(Keyword::Try) => return parse_try(p),
+    TokenKind::Keyword(Keyword::Else) => return Err(WoojinError::new("else without an if", WoojinErrorKind::ElseParsingFailed).with_span(start)),
+    TokenKind::Keyword(Keyword::Catch) => return Err(WoojinError::new("catch without a try", WoojinErrorKind::TryParsingFailed).with_span(start)),
+    TokenKind::Keyword(Keyword::Let) => parse_let(p)?,
+    TokenKind::Keyword(Keyword::Print) => { p.advance(); Statements::Print { values: parse_values(p)? } },
+    TokenKind::Keyword(Keyword::Println) => { p.advance(); Statements::Println { values: parse_values(p)? } },
+    TokenKind::Keyword(Keyword::Roar) => {
+      p.advance();
+      let value: Stmt = parse_value(p)?;
+      let code: Option<Box<Stmt>> = if p.eat(&TokenKind::Symbol(",")) { Some(Box::new(parse_value(p)?)) } else { None };
+      Statements::Roar { value: Box::new(value), code }
+    },
+    TokenKind::Keyword(Keyword::Yee) => { p.advance(); Statements::Yee { code: parse_exit_code(p)? } },
+    TokenKind::Keyword(Keyword::Sleep) => { p.advance(); Statements::Sleep { value: Box::new(parse_value(p)?) } },
+    TokenKind::Keyword(Keyword::SetEnv) => {
+      p.advance();
+      let name: Stmt = parse_value(p)?;
+      p.expect(&TokenKind::Symbol(","), "between the name and the value of setenv")?;
+      Statements::SetEnv { name: Box::new(name), value: Box::new(parse_value(p)?) }
+    },
+    TokenKind::Keyword(Keyword::Return) => {
+      p.advance();
+      Statements::Return { value: if p.at_end() { None } else { Some(Box::new(parse_value(p)?)) } }
+    },
+    TokenKind::Keyword(Keyword::Break) => { p.advance(); Statements::Break },
+    TokenKind::Keyword(Keyword::Continue) => { p.advance(); Statements::Continue },
+    TokenKind::Var(name) if p.nth(1).kind == TokenKind::Symbol("=") => {
+      p.advance();
+      p.advance();
+      Statements::Assignment { name, value: Box::new(parse_value(p)?) }
+    },
+    TokenKind::Var(name) if p.nth(1).kind == TokenKind::Symbol("[") => match parse_index_target(p)? {
+      Some(indices) => Statements::IndexAssignment { name, indices, value: Box::new(parse_value(p)?) },
+      None => parse_value(p)?.node
+    },
+    _ => parse_value(p)?.node
   };
-  Ok(Statements::Input { question: Box::new(tokenize_line(&input.to_string())?) })
+  let span: Span = p.span_from(start);
+  // A `;` ends the statement like the end of the line does, and another statement may follow it.
+  if !p.eat(&TokenKind::Symbol(";")) || p.peek().kind == TokenKind::Newline {
+    p.expect(&TokenKind::Newline, "after the statement")?;
+  }
+  Ok(Spanned { node, span })
 }
 
-pub(crate) fn sleep(i: &str) -> WoojinResult<Statements> {
-  let input: &str = match tag::<&str, _, nom::error::Error<&str>>("sleep ")(i) {
-    Ok((input, _)) => input,
-    Err(_) => return Err(WoojinError::new("Invalid usage of sleep", crate::error::WoojinErrorKind::Unknown))
+/// Parses something with a value: an expression, or `input`, which reads one.
+pub(crate) fn parse_value(p: &mut Parser) -> WoojinResult<Stmt> {
+  let start: Span = p.peek().span;
+  let node: Statements = match p.peek().kind {
+    TokenKind::Keyword(Keyword::Input) => {
+      p.advance();
+      p.enter()?;
+      let question: Stmt = parse_value(p)?;
+      p.leave(1);
+      Statements::Input { question: Box::new(question) }
+    },
+    _ => {
+      let expr: Expr = parse_calc(p)?;
+      match expr.node {
+        Calc::Value(value) => Statements::Value { value },
+        _ => Statements::Calc(expr)
+      }
+    }
   };
-  Ok(Statements::Sleep { value: Box::new(tokenize_line(&input.to_string())?) })
+  Ok(Spanned { node, span: p.span_from(start) })
 }
 
-pub(crate) fn parse_variable(input: &str) -> IResult<&str, (String, String, &str, bool)> {
-  let (input, _): (&str, &str) = multispace0(input)?;
-  let (input, mutable): (&str, bool) = alt((
-      value(true, preceded(tag("let"), preceded(multispace1, tag("mut")))),
-      value(false, preceded(tag("let"), multispace1)),
-  ))(input)?;
-  let (input, _): (&str, &str) = multispace0(input)?;
-  let (input, var_name): (&str, String) = map(
-      take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_'),
-      |s: &str| s.to_string(),
-  )(input)?;
-  let (input, _): (&str, &str) = multispace0(input)?;
-  let (input, _): (&str, Option<&str>) = opt(tag(":"))(input)?;
-  let (input, _): (&str, &str) = multispace0(input)?;
-  let (input, var_type): (&str, Option<String>) = map(
-      opt(take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_')),
-      |s: Option<&str>| s.map(|s| s.to_string()),
-  )(input)?;
-  let var_type: String = if let Some(s) = var_type { s } else { "".to_string()};
-  let (input, _): (&str, &str) = multispace0(input)?;
-  let (input, _): (&str, char) = char('=')(input)?;
-  let (input, _): (&str, &str) = multispace0(input)?;
-  let (input, value): (&str, &str) = take_while1(|c: char| c != ';' && c != '\n')(input)?;
-  Ok((input, (var_name, var_type, value, mutable)))
+/// The values of `print` and `println`, separated by commas. There may be none.
+fn parse_values(p: &mut Parser) -> WoojinResult<Vec<Stmt>> {
+  let mut values: Vec<Stmt> = Vec::new();
+  if p.at_end() { return Ok(values); }
+  values.push(parse_value(p)?);
+  while p.eat(&TokenKind::Symbol(",")) { values.push(parse_value(p)?); }
+  Ok(values)
 }
 
-// pub(crate) fn parse_assignment(input: &str) -> WoojinResult<(String, String)>{
-//   let splited: Vec<String> = input.split("=").map(|a| a.to_string()).collect();
-//   if splited.len() < 2 { return Err(WoojinError::new("Invalid assignment", crate::error::WoojinErrorKind::InvaildAssignment)); }
-//   let (_, varname) = parse_variable_name(splited[0].trim())?;
-//   let value: String = splited[1].trim().to_string();
-//   Ok((varname, value))
-// }
-
-fn parse_whitespace(input: &str) -> IResult<&str, &str> {
-  take_while(|c: char| c.is_whitespace())(input)
+/// `yee` takes a literal integer, possibly negative.
+fn parse_exit_code(p: &mut Parser) -> WoojinResult<i32> {
+  let negative: bool = p.eat(&TokenKind::Symbol("-"));
+  let token: Token = p.peek().clone();
+  let code: Option<i32> = match &token.kind {
+    TokenKind::Number(digits) => format!("{}{}", if negative { "-" } else { "" }, digits).parse::<i32>().ok(),
+    _ => None
+  };
+  match code {
+    Some(code) => { p.advance(); Ok(code) },
+    None => Err(p.unexpected("an exit code").with_help("yee takes a whole number, like `yee 0`"))
+  }
 }
 
-fn parse_if_condition(input: &str) -> IResult<&str, &str> {
-  preceded(tag("if "), terminated(take_while(|c: char| c != ':'), tag(":")))(input)
+/// `let [mut] name[: type] = value`.
+fn parse_let(p: &mut Parser) -> WoojinResult<Statements> {
+  p.advance();
+  let mutable: bool = p.eat(&TokenKind::Keyword(Keyword::Mut));
+  // Variables are always used with a `$`, so even a keyword can name one.
+  let name: String = match p.peek().kind.clone() {
+    TokenKind::Ident(name) => name,
+    TokenKind::Keyword(keyword) => keyword.as_str().to_string(),
+    _ => return Err(p.unexpected("a variable name").with_help("write the name without a `$`, like `let count = 0`"))
+  };
+  p.advance();
+  let kind: WoojinValueKind = if p.eat(&TokenKind::Symbol(":")) { parse_type(p)? } else { WoojinValueKind::Any };
+  p.expect(&TokenKind::Symbol("="), "after the variable name")?;
+  Ok(Statements::Let {
+    name,
+    stmt: Box::new(parse_value(p)?),
+    kind,
+    option: VariableOption::new(Some(mutable), None)
+  })
 }
 
-fn check_is_else(input: &str) -> IResult<&str, &str> {
-  preceded(tag("else"), terminated(parse_whitespace, tag(":")))(input)
+fn parse_type(p: &mut Parser) -> WoojinResult<WoojinValueKind> {
+  match p.peek().kind.clone() {
+    TokenKind::Ident(name) => {
+      let span: Span = p.advance().span;
+      WoojinValueKind::from_str(&name).map_err(|e| e.with_span(span))
+    },
+    _ => Err(p.unexpected("a type"))
+  }
 }
 
-fn is_else(input: &str) -> bool {
-  match check_is_else(input) {
-    Ok(_) => true,
-    Err(_) => false
-  }
+/// The `[index]...` and `=` of an index assignment. Gives `None`, without moving,
+/// when the statement turns out to be an expression like `$a[0] + 1`.
+fn parse_index_target(p: &mut Parser) -> WoojinResult<Option<Vec<Expr>>> {
+  let start: usize = p.pos;
+  p.advance();
+  let mut indices: Vec<Expr> = Vec::new();
+  while p.peek().kind == TokenKind::Symbol("[") { indices.push(parse_index(p)?); }
+  if p.eat(&TokenKind::Symbol("=")) { return Ok(Some(indices)); }
+  p.pos = start;
+  Ok(None)
 }
 
-pub(crate) fn test(input: &str) -> WoojinResult<WoojinValue> {
-  match tokenize_line(&input.to_string())? {
-    Statements::Value { value: val } => Ok(val),
-    a => match exec(&a) {
-      Ok(val) => Ok(val),
-      Err(e) => Err(e)
-    }
+fn parse_if(p: &mut Parser) -> WoojinResult<Stmt> {
+  let start: Span = p.advance().span;
+  let condition: Stmt = parse_value(p)?;
+  p.expect(&TokenKind::Symbol(":"), "after the condition of if")?;
+  let span: Span = p.span_from(start);
+  let stmt: Vec<Stmt> = parse_block(p, WoojinError::new("Parsing If statement failed", WoojinErrorKind::IfParsingFailed).with_help(BLOCK_HELP).with_span(span))?;
+  let mut else_stmt: Vec<Stmt> = vec![];
+  if p.peek().kind == TokenKind::Keyword(Keyword::Else) {
+    let else_start: Span = p.advance().span;
+    p.expect(&TokenKind::Symbol(":"), "after else")?;
+    let else_span: Span = p.span_from(else_start);
+    else_stmt = parse_block(p, WoojinError::new("Parsing Else statement failed", WoojinErrorKind::ElseParsingFailed).with_help(BLOCK_HELP).with_span(else_span))?;
   }
+  Ok(Spanned { node: Statements::If { condition: Box::new(condition), stmt, else_stmt }, span })
 }
 
-pub(crate) fn parse_variable_name(input: &str) -> IResult<&str, String> {
-  let (input, a): (&str, &str) = preceded(char('$'), take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_'))(input)?;
-  Ok((input, a.to_string()))
+fn parse_while(p: &mut Parser) -> WoojinResult<Stmt> {
+  let start: Span = p.advance().span;
+  let condition: Stmt = parse_value(p)?;
+  p.expect(&TokenKind::Symbol(":"), "after the condition of while")?;
+  let span: Span = p.span_from(start);
+  let body: Vec<Stmt> = parse_block(p, WoojinError::new("Expected an indented block after while", WoojinErrorKind::WhileParsingFailed).with_help(BLOCK_HELP).with_span(span))?;
+  Ok(Spanned { node: Statements::While { condition: Box::new(condition), body }, span })
 }
 
-pub(crate) fn tokenizer(lines: &Vec<(usize, String)>) -> WoojinResult<Vec<Statements>> {
-  let mut pointer: usize = 0;
-  let mut result: Vec<Statements> = vec![];
-  while pointer < lines.len() {
-    let (indent, line): &(usize, String) = &lines[pointer];
-    let mut tokenized: Statements = tokenize_line(line)?.clone();
-    match &mut tokenized {
-      Statements::If { condition: _, stmt, else_stmt} => {
-        let (if_stmt, e_stmt): (Vec<Box<Statements>>, Vec<Box<Statements>>) = parse_if(lines, &mut pointer, *indent)?;
-        *stmt = if_stmt;
-        *else_stmt = e_stmt;
-        result.push(tokenized)
-      }
-      _ => result.push(tokenized),
-    }
-    pointer += 1;
-  }
-  Ok(result)
+/// `for $name in start..end:` or `for $name in array:`.
+fn parse_for(p: &mut Parser) -> WoojinResult<Stmt> {
+  let start: Span = p.advance().span;
+  let name: String = match p.peek().kind.clone() {
+    TokenKind::Var(name) => { p.advance(); name },
+    _ => return Err(p.unexpected("a `$variable` after for").with_help("a for loop looks like `for $i in 0..10:`"))
+  };
+  p.expect(&TokenKind::Keyword(Keyword::In), "after the loop variable")?;
+  let first: Stmt = parse_value(p)?;
+  let iter: Iterable = if p.eat(&TokenKind::Symbol("..")) {
+    Iterable::Range { start: Box::new(first), end: Box::new(parse_value(p)?) }
+  } else {
+    Iterable::Value(Box::new(first))
+  };
+  p.expect(&TokenKind::Symbol(":"), "after the loop header")?;
+  let span: Span = p.span_from(start);
+  let body: Vec<Stmt> = parse_block(p, WoojinError::new("Expected an indented block after for", WoojinErrorKind::ForParsingFailed).with_help(BLOCK_HELP).with_span(span))?;
+  Ok(Spanned { node: Statements::For { name, iter, body }, span })
 }
 
-pub(crate) fn parse_if(lines: &Vec<(usize, String)>, pointer: &mut usize, indent: usize) -> WoojinResult<(Vec<Box<Statements>>, Vec<Box<Statements>>)> {
-  let mut result: (Vec<Box<Statements>>, Vec<Box<Statements>>) = (vec![], vec![]);
-  *pointer += 1;
-  while *pointer < lines.len() {
-    let (line_indent, line): &&(usize, String) = &lines.get(*pointer).ok_or(WoojinError::new("Invalid indent", crate::error::WoojinErrorKind::InvalidIndent))?;
-    if *line_indent <= indent {
-      if !is_else(line) {
-        *pointer -= 1;
-        return Ok(result);
-      }
-      result.1 = parse_else(lines, &mut *pointer, *line_indent)?;
-      return Ok(result);
-    }
-    let mut tokenized: Statements = tokenize_line(line)?;
-    match &mut tokenized {
-      Statements::If { condition: _, stmt, else_stmt } => {
-        let (if_stmt, e_stmt): (Vec<Box<Statements>>, Vec<Box<Statements>>) = parse_if(lines, &mut *pointer, *line_indent)?;
-        *stmt = if_stmt;
-        *else_stmt = e_stmt;
-        result.0.push(Box::new(tokenized));
-      }
-      _ => result.0.push(Box::new(tokenized))
-    }
-    *pointer += 1;
+/// `fn name($param: type, ...):`. The types of the parameters are optional.
+fn parse_fn(p: &mut Parser) -> WoojinResult<Stmt> {
+  let start: Span = p.advance().span;
+  let name: String = match p.peek().kind.clone() {
+    TokenKind::Ident(name) => { p.advance(); name },
+    _ => return Err(p.unexpected("a function name"))
+  };
+  p.expect(&TokenKind::Symbol("("), "after the function name")?;
+  let mut params: Vec<(String, WoojinValueKind)> = Vec::new();
+  while p.peek().kind != TokenKind::Symbol(")") {
+    let param: String = match p.peek().kind.clone() {
+      TokenKind::Var(name) => { p.advance(); name },
+      _ => return Err(p.unexpected("a `$parameter`"))
+    };
+    let kind: WoojinValueKind = if p.eat(&TokenKind::Symbol(":")) { parse_type(p)? } else { WoojinValueKind::Any };
+    params.push((param, kind));
+    if !p.eat(&TokenKind::Symbol(",")) { break; }
   }
-  Err(WoojinError::new("Parsing If statement failed", crate::error::WoojinErrorKind::IfParsingFailed))
+  p.expect(&TokenKind::Symbol(")"), "after the parameters")?;
+  p.expect(&TokenKind::Symbol(":"), "after the parameters")?;
+  let span: Span = p.span_from(start);
+  let body: Vec<Stmt> = parse_block(p, WoojinError::new("Expected an indented block after fn", WoojinErrorKind::FunctionParsingFailed).with_help(BLOCK_HELP).with_span(span))?;
+  Ok(Spanned { node: Statements::Function { name, params, body }, span })
 }
 
-pub(crate) fn parse_else(lines: &Vec<(usize, String)>, pointer: &mut usize, indent: usize) -> WoojinResult<Vec<Box<Statements>>> {
-  let mut result: Vec<Box<Statements>> = vec![];
-  *pointer += 1;
-  while *pointer < lines.len() {
-    let (line_indent, line): &&(usize, String) = &lines.get(*pointer).ok_or(WoojinError::new("Invalid indent", crate::error::WoojinErrorKind::InvalidIndent))?;
-    if *line_indent <= indent {
-      *pointer = *pointer-1;
-      return Ok(result);
-    }
-    let mut tokenized: Statements = tokenize_line(line)?;
-    match &mut tokenized {
-      Statements::If { condition: _, stmt, else_stmt } => {
-        let (if_stmt, e_stmt): (Vec<Box<Statements>>, Vec<Box<Statements>>) = parse_if(lines, &mut *pointer, *line_indent)?;
-        *stmt = if_stmt;
-        *else_stmt = e_stmt;
-        result.push(Box::new(tokenized));
-      }
-      _ => result.push(Box::new(tokenized))
-    }
-    *pointer += 1;
+/// `try:` and the `catch:` or `catch $name:` that has to follow its block.
+fn parse_try(p: &mut Parser) -> WoojinResult<Stmt> {
+  let start: Span = p.advance().span;
+  p.expect(&TokenKind::Symbol(":"), "after try")?;
+  let span: Span = p.span_from(start);
+  let body: Vec<Stmt> = parse_block(p, WoojinError::new("Expected an indented block after try", WoojinErrorKind::TryParsingFailed).with_help(BLOCK_HELP).with_span(span))?;
+  if p.peek().kind != TokenKind::Keyword(Keyword::Catch) {
+    return Err(WoojinError::new("try must be followed by catch", WoojinErrorKind::TryParsingFailed).with_help("add a `catch $err:` block after the body of the try").with_span(span));
   }
-  Err(WoojinError::new("Parsing Else statement failed", crate::error::WoojinErrorKind::ElseParsingFailed))
+  let catch_start: Span = p.advance().span;
+  let name: Option<String> = match p.peek().kind.clone() {
+    TokenKind::Var(name) => { p.advance(); Some(name) },
+    _ => None
+  };
+  p.expect(&TokenKind::Symbol(":"), "after catch")?;
+  let catch_span: Span = p.span_from(catch_start);
+  let handler: Vec<Stmt> = parse_block(p, WoojinError::new("Expected an indented block after catch", WoojinErrorKind::TryParsingFailed).with_help(BLOCK_HELP).with_span(catch_span))?;
+  Ok(Spanned { node: Statements::Try { body, name, handler }, span })
 }
-
-pub(crate) fn tokenize_line(line: &str) -> WoojinResult<Statements> {
-  let line: String = line.to_string().trim().to_string();
-  let chvar_reg = Regex::new(r"(?m)\$[a-zA-Z_]{1}[a-zA-Z0-9_]*\s*=\s*").unwrap();
-  match line {
-    line if line == "" => Ok(Statements::Value { value: WoojinValue::String("".to_string()) }),
-    line if line.starts_with("if") => {
-      let (_, condition): (&str, &str) = parse_if_condition(&line)?;
-      let condition: Statements = tokenize_line(&condition.to_string())?;
-      Ok(Statements::If { condition: Box::new(condition), stmt: Vec::new(), else_stmt: Vec::new() })
-    },
-    line if chvar_reg.is_match(line.as_str()) => {
-      let splited = line.split("=").map(|a| a.to_string()).collect::<Vec<String>>();
-      let (_, varname) = parse_variable_name(splited[0].trim())?;
-      let stmts: Statements = tokenize_line(splited[1..].join("=").trim())?;
-      Ok(Statements::Assignment { name: varname, value: Box::new(stmts) })
-    },
-    line if line.starts_with("else") => {Ok(Statements::Value { value: WoojinValue::Unit })},
-    line if line.starts_with("//") => Ok(Statements::Comment(line[2..].trim().to_string())),
-    line if line.starts_with("yee") => match yee(&line)? { (_, a) => { return Ok(a); }, }
-    line if line.starts_with("println") => Ok(println(&line)?),
-    line if line.starts_with("print") => Ok(print(&line)?),
-    line if line.starts_with("roar") => Ok(roar(&line)?),
-    line if line.starts_with("input") => Ok(input(&line)?),
-    line if line.starts_with("sleep") => Ok(sleep(&line)?),
-    line if line.starts_with("let") => {
-      let (_, (var_name, kind, input, mutable)): (&str, (String, String, &str, bool)) = parse_variable(&line)?;
-      let stmts: Statements = tokenize_line(&input.to_string())?;
-      Ok(Statements::Let {
-        name: var_name,
-        stmt: Box::new(stmts),
-        kind: if kind.is_empty() { WoojinValueKind::Any } else { WoojinValueKind::from_str(&kind)? },
-        option: VariableOption::new(Some(mutable), None)
-      })
-    },
-    _ => match parse_calc(line.as_str()) {
-      Ok(val) => {
-        match val.1 {
-          Calc::Value(a) => Ok(Statements::Value {value: a}),
-          _ => Ok(Statements::Calc(val.1))
-        }
-      },
-      _ => match parse_value(line.as_str()) {
-        Ok(val) => Ok(Statements::Value {value: val.1}),
-        Err(_) => Err(WoojinError::new(format!("Unknown token \"{}\"", line), crate::error::WoojinErrorKind::UnknownToken))
-      }
-    }
-  }
-}
\ No newline at end of file
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use rustyline::{DefaultEditor, error::ReadlineError};
+use woojin::{Interpreter, types::WoojinValue};
+
+const PROMPT: &str = ">>> ";
+const CONTINUATION: &str = "... ";
+
+/// Where the history of the REPL is kept between sessions.
+fn history_path() -> Option<PathBuf> {
+  std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".woojin_history"))
+}
+
+/// Runs the interactive read-eval-print loop until end of input or `yee`.
+///
+/// Every entry runs in the same interpreter, so variables and functions stay around.
+/// A line ending in `:` opens a block: the following lines are read with the `...`
+/// prompt until an empty line, and then run together.
+pub(crate) fn run() {
+  let mut editor: DefaultEditor = match DefaultEditor::new() {
+    Ok(editor) => editor,
+    Err(e) => {
+      eprintln!("Failed to start the REPL: {}", e);
+      std::process::exit(1);
+    }
+  };
+  let history: Option<PathBuf> = history_path();
+  if let Some(path) = &history { let _ = editor.load_history(path); }
+  println!("woojin {} (type `yee 0` or press Ctrl-D to quit)", env!("CARGO_PKG_VERSION"));
+
+  let mut interpreter: Interpreter = Interpreter::new();
+  let mut entry: String = String::new();
+  loop {
+    let line: String = match editor.readline(if entry.is_empty() { PROMPT } else { CONTINUATION }) {
+      Ok(line) => line,
+      // Ctrl-C drops the entry being typed, like in a shell.
+      Err(ReadlineError::Interrupted) => { entry.clear(); continue; },
+      Err(ReadlineError::Eof) => break,
+      Err(e) => {
+        eprintln!("Failed to read the input: {}", e);
+        break;
+      }
+    };
+    if !line.trim().is_empty() { let _ = editor.add_history_entry(line.as_str()); }
+
+    if entry.is_empty() && line.trim().is_empty() { continue; }
+    let in_block: bool = !entry.is_empty() || line.trim_end().trim_end_matches(';').ends_with(':');
+    entry.push_str(&line);
+    entry.push('\n');
+    // A block ends with an empty line.
+    if in_block && !line.trim().is_empty() { continue; }
+
+    match interpreter.eval_str(&entry) {
+      Ok(WoojinValue::Unit) => {},
+      Ok(value) => println!("{}", value.to_print()),
+      Err(e) if e.exit_code().is_some() => {
+        if let Some(path) = &history { let _ = editor.save_history(path); }
+        e.exit();
+      },
+      Err(e) => println!("{}", e)
+    }
+    entry.clear();
+  }
+  if let Some(path) = &history { let _ = editor.save_history(path); }
+}
@@ -1,29 +1,32 @@
 use std::str::FromStr;
 
-use crate::{variable::{get_var}, error::WoojinError};
+use crate::error::{WoojinError, WoojinErrorKind};
 pub(crate) mod parse;
 
+#[allow(dead_code)]
 pub(crate) trait ToWoojinValue {
   fn to_woojin_value(&self) -> WoojinValue;
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum WoojinValue {
+pub enum WoojinValue {
   Bool(bool),
   String(String),
   Int(i32),
-  Long(i64), // Not yet supported
-  Float(f32), // Not yet supported
-  Double(f64), // Not yet supported
-  Array(Vec<WoojinValue>), // Not yet supported
+  Long(i64),
+  Float(f32),
+  Double(f64),
+  Array(Vec<WoojinValue>),
+  /// An error caught by `catch $err:`.
+  Error { kind: WoojinErrorKind, message: String },
   Var(String),
   Unit,
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub(crate) enum WoojinValueKind {
+pub enum WoojinValueKind {
   Bool,
   String,
   Int,
@@ -31,19 +34,13 @@ pub(crate) enum WoojinValueKind {
   Float,
   Double,
   Array,
+  Error,
   Unit,
   Any
 }
 
 impl WoojinValue {
-  pub(crate) fn value(&self) -> WoojinValue {
-    match self {
-      WoojinValue::Var(name) => { get_var(name.as_str()).value.clone() },
-      _ => self.clone(),
-    }
-  }
-
-  pub(crate) fn kind(&self) -> WoojinValueKind {
+  pub fn kind(&self) -> WoojinValueKind {
     match self {
       WoojinValue::Bool(_) => WoojinValueKind::Bool,
       WoojinValue::String(_) => WoojinValueKind::String,
@@ -52,7 +49,9 @@ impl WoojinValue {
       WoojinValue::Float(_) => WoojinValueKind::Float,
       WoojinValue::Double(_) => WoojinValueKind::Double,
       WoojinValue::Array(_) => WoojinValueKind::Array,
-      WoojinValue::Var(name) => get_var(name.as_str()).value.kind(),
+      WoojinValue::Error { .. } => WoojinValueKind::Error,
+      // Variables are resolved by the interpreter before their kind matters.
+      WoojinValue::Var(_) => WoojinValueKind::Any,
       WoojinValue::Unit => WoojinValueKind::Unit
     }
   }
@@ -62,21 +61,62 @@ impl WoojinValue {
     self.kind() == other
   }
 
-  pub(crate) fn to_print(&self) -> String {
+  /// Converts a number to a wider numeric kind. Returns `None` if that would narrow it.
+  pub(crate) fn widen(&self, kind: WoojinValueKind) -> Option<WoojinValue> {
+    match (self, kind) {
+      (value, kind) if value.kind() == kind => Some(value.clone()),
+      (WoojinValue::Int(a), WoojinValueKind::Long) => Some(WoojinValue::Long(*a as i64)),
+      (WoojinValue::Int(a), WoojinValueKind::Float) => Some(WoojinValue::Float(*a as f32)),
+      (WoojinValue::Int(a), WoojinValueKind::Double) => Some(WoojinValue::Double(*a as f64)),
+      (WoojinValue::Long(a), WoojinValueKind::Double) => Some(WoojinValue::Double(*a as f64)),
+      (WoojinValue::Float(a), WoojinValueKind::Double) => Some(WoojinValue::Double(*a as f64)),
+      _ => None
+    }
+  }
+
+  /// Makes the value fit a declared type, widening numbers where needed
+  /// (so `let x: long = 10` holds a long). Returns `None` if the types don't match.
+  pub(crate) fn coerce(&self, kind: WoojinValueKind) -> Option<WoojinValue> {
+    if self.type_eq(kind) { return Some(self.clone()); }
+    match WoojinValueKind::common(self.kind(), kind) {
+      Some(common) if common == kind => self.widen(kind),
+      _ => None
+    }
+  }
+
+  pub fn to_print(&self) -> String {
     match self {
-      WoojinValue::Bool(a) => if a.clone() { String::from("uglyguri") } else { String::from("beautifulguri") },
+      WoojinValue::Bool(a) => if *a { String::from("uglyguri") } else { String::from("beautifulguri") },
       WoojinValue::Int(a) => a.to_string(),
       WoojinValue::Long(a) => a.to_string(),
       WoojinValue::Float(a) => a.to_string(),
       WoojinValue::Double(a) => a.to_string(),
       WoojinValue::String(a) => a.to_string(),
       WoojinValue::Array(a) => format!("[{}]", a.iter().map(|a| a.to_print()).collect::<Vec<String>>().join(", ")),
-      WoojinValue::Var(name) => get_var(name.as_str()).value.to_print(),
+      WoojinValue::Error { kind, message } => format!("{:?}: {}", kind, message),
+      WoojinValue::Var(name) => format!("${}", name),
       WoojinValue::Unit => "()".to_string()
     }
   }
 }
 
+impl WoojinValueKind {
+  /// The kind two numbers are widened to before they are combined:
+  /// int < long and float < double, a floating point operand makes the result
+  /// floating point, and long mixed with float becomes double, because a float
+  /// can't hold every long. Returns `None` unless both kinds are numeric.
+  pub(crate) fn common(a: WoojinValueKind, b: WoojinValueKind) -> Option<WoojinValueKind> {
+    use WoojinValueKind::*;
+    match (a, b) {
+      (Int, Int) => Some(Int),
+      (Int | Long, Int | Long) => Some(Long),
+      (Int | Float, Int | Float) => Some(Float),
+      (Int | Long | Float | Double, Int | Long | Float | Double) => Some(Double),
+      _ => None
+    }
+  }
+}
+
 impl FromStr for WoojinValueKind {
   type Err = WoojinError;
 
@@ -89,24 +129,26 @@ impl FromStr for WoojinValueKind {
       "float" => Ok(WoojinValueKind::Float),
       "double" => Ok(WoojinValueKind::Double),
       "array" => Ok(WoojinValueKind::Array),
+      "error" => Ok(WoojinValueKind::Error),
       "unit" => Ok(WoojinValueKind::Unit),
-      _ => Err(WoojinError::new(format!("Invalid type: {}", s), crate::error::WoojinErrorKind::InvalidType))
+      _ => Err(WoojinError::new(format!("Invalid type: {}", s), WoojinErrorKind::InvalidType))
     }
   }
 }
 
-impl ToString for WoojinValueKind {
-  fn to_string(&self) -> String {
-    match self {
-      WoojinValueKind::Bool => String::from("bool"),
-      WoojinValueKind::String => String::from("string"),
-      WoojinValueKind::Int => String::from("int"),
-      WoojinValueKind::Long => String::from("long"),
-      WoojinValueKind::Float => String::from("float"),
-      WoojinValueKind::Double => String::from("double"),
-      WoojinValueKind::Array => String::from("array"),
-      WoojinValueKind::Unit => String::from("unit"),
-      WoojinValueKind::Any => String::from("any")
-    }
+impl std::fmt::Display for WoojinValueKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{}", match self {
+      WoojinValueKind::Bool => "bool",
+      WoojinValueKind::String => "string",
+      WoojinValueKind::Int => "int",
+      WoojinValueKind::Long => "long",
+      WoojinValueKind::Float => "float",
+      WoojinValueKind::Double => "double",
+      WoojinValueKind::Array => "array",
+      WoojinValueKind::Error => "error",
+      WoojinValueKind::Unit => "unit",
+      WoojinValueKind::Any => "any"
+    })
   }
 }
\ No newline at end of file
@@ -1,18 +1,11 @@
 use nom::{
   branch::alt,
-  bytes::complete::{
-    tag, escaped, is_not
-  },
   character::complete::{char, digit1},
   combinator::{map, map_res, opt, recognize},
-  sequence::{delimited, pair, tuple},
+  sequence::{pair, terminated, tuple},
   IResult,
 };
 
-use crate::{
-  parser::{parse_variable_name}
-};
-
 use super::WoojinValue;
 
 // Integer(signed)
@@ -26,6 +19,20 @@ pub(crate) fn parse_int(input: &str) -> IResult<&str, i32> {
   )(input)
 }
 
+// Long: an integer with an `L` suffix, e.g. `10L`
+pub(crate) fn parse_long(input: &str) -> IResult<&str, i64> {
+  map_res(
+    terminated(
+      recognize(pair(
+        opt(alt((char('+'), char('-')))),
+        digit1,
+      )),
+      alt((char('L'), char('l'))),
+    ),
+    |s: &str| s.parse::<i64>(),
+  )(input)
+}
+
 pub(crate) fn parse_float(input: &str) -> IResult<&str, f32> {
   map_res(
     recognize(tuple((
@@ -37,43 +44,27 @@ pub(crate) fn parse_float(input: &str) -> IResult<&str, f32> {
   )(input)
 }
 
-pub(crate) fn parse_string(input: &str) -> IResult<&str, String> {
-  let re: regex::Regex = regex::Regex::new(r"\\").unwrap();
-  let result: (&str, &str) = delimited(
-    char('"'),
-    escaped(
-      is_not("\\\""),
-      '\\',
-      alt((
-        tag("\\"),
-        tag("\""),
-        tag("t"),
-        tag("n"),
-        tag("r"),
-        tag("0"),
-      )),
+// Double: a float with a `d` suffix, e.g. `1.5d`
+pub(crate) fn parse_double(input: &str) -> IResult<&str, f64> {
+  map_res(
+    terminated(
+      recognize(tuple((
+        opt(alt((char('+'), char('-')))),
+        pair(digit1, char('.')),
+        digit1,
+      ))),
+      alt((char('d'), char('D'))),
     ),
-    char('"'),
-  )(input)?;
-  let unescaped = result.1.replace("\\t", "\t").replace("\\r", "\r").replace("\\n", "\n");
-  Ok((result.0, re.replace_all(&unescaped, "").to_string()))
-}
-
-// Boolean
-pub(crate) fn parse_bool(input: &str) -> IResult<&str, bool> {
-  alt((
-    map(tag("uglyguri"), |_| true),
-    map(tag("beautifulguri"), |_| false),
-  ))(input)
+    |s: &str| s.parse::<f64>(),
+  )(input)
 }
 
-// parse value
-pub(crate) fn parse_value(input: &str) -> IResult<&str, WoojinValue> {
+// Any numeric literal
+pub(crate) fn parse_number(input: &str) -> IResult<&str, WoojinValue> {
   alt((
-    map(parse_string, WoojinValue::String),
+    map(parse_double, WoojinValue::Double),
     map(parse_float, WoojinValue::Float),
+    map(parse_long, WoojinValue::Long),
     map(parse_int, WoojinValue::Int),
-    map(parse_bool, WoojinValue::Bool),
-    map(parse_variable_name, WoojinValue::Var),
   ))(input)
-}
\ No newline at end of file
+}
@@ -1,8 +1,5 @@
-use std::{
-  sync::{Arc, Mutex}, collections::HashMap
-};
+use std::collections::HashMap;
 use crate::{types::{WoojinValue, WoojinValueKind}, error::{WoojinError, WoojinErrorKind}};
-use lazy_static::lazy_static;
 
 #[derive(Debug, Clone)]
 pub(crate) struct VariableOption {
@@ -23,42 +20,95 @@ pub(crate) struct WoojinVariable {
   pub kind: WoojinValueKind,
   pub is_mut: bool
 }
-lazy_static! {
-  pub(crate) static ref VARS: Arc<Mutex<HashMap<String, WoojinVariable>>> = {
-    Arc::new(Mutex::new(HashMap::new()))
-  };
+
+/// The variables of a single program. Every `Program` owns its own environment,
+/// so programs running side by side never see each other's bindings.
+///
+/// Variables live in a chain of scopes: the first one is the global scope and every
+/// block pushes a new one on entry and pops it on exit. Lookups walk from the innermost
+/// scope outwards, and a `let` in an inner scope may shadow an outer binding.
+#[derive(Debug, Clone)]
+pub(crate) struct Environment {
+  scopes: Vec<Scope>
 }
 
-pub(crate) fn get_var(name: &str) -> WoojinVariable {
-  let vars: std::sync::MutexGuard<HashMap<String, WoojinVariable>> = VARS.lock().unwrap();
-  if !vars.contains_key(name) {
-    WoojinError::new(format!("Variable {} is not declared", name), WoojinErrorKind::UndeclaredVariable).exit();
+pub(crate) type Scope = HashMap<String, WoojinVariable>;
+
+impl Default for Environment {
+  fn default() -> Self {
+    Environment::new()
   }
-  vars.get(name).unwrap().clone()
 }
 
-pub(crate) fn change_var(name: &str, value: &WoojinValue) -> Result<(), WoojinError> {
-  let var: WoojinVariable = get_var(name);
-  if !var.is_mut { WoojinError::new(format!("Variable {} is not mutable", name), WoojinErrorKind::VariableNotMutable).exit(); }
-  let mut vars: std::sync::MutexGuard<HashMap<String, WoojinVariable>> = VARS.lock().unwrap();
-  if !value.type_eq(var.kind) { WoojinError::new(format!("Variable {} is not {}", name, value.kind().to_string()), WoojinErrorKind::TypeMismatch).exit(); }
-  vars.insert(name.to_string(), WoojinVariable {
-    value: value.clone(),
-    kind: var.kind,
-    is_mut: var.is_mut
-  });
-  Ok(())
-}
+impl Environment {
+  pub(crate) fn new() -> Environment {
+    Environment { scopes: vec![HashMap::new()] }
+  }
 
-pub(crate) fn dec_var(name: &str, value: &WoojinValue, option: &VariableOption) -> Result<(), WoojinError> {
-  let mut vars: std::sync::MutexGuard<HashMap<String, WoojinVariable>> = VARS.lock().unwrap();
-  if vars.contains_key(name) { WoojinError::new(format!("Variable {} is already declared", name), WoojinErrorKind::VariableAlreadyDeclared).exit(); }
-  vars.insert(name.to_string(), WoojinVariable {
-    value: value.clone(),
-    kind: value.kind(),
-    is_mut: option.is_mut
-  });
-  Ok(())
-}
+  pub(crate) fn push_scope(&mut self) {
+    self.scopes.push(HashMap::new());
+  }
+
+  pub(crate) fn pop_scope(&mut self) {
+    if self.scopes.len() > 1 { self.scopes.pop(); }
+  }
 
+  /// Hides every scope but the global one behind a fresh scope for a function call.
+  /// The hidden scopes are handed back and must be restored with [`Environment::leave_frame`].
+  pub(crate) fn enter_frame(&mut self) -> Vec<Scope> {
+    let saved: Vec<Scope> = self.scopes.split_off(1);
+    self.scopes.push(HashMap::new());
+    saved
+  }
+
+  pub(crate) fn leave_frame(&mut self, saved: Vec<Scope>) {
+    self.scopes.truncate(1);
+    self.scopes.extend(saved);
+  }
 
+  fn find_scope(&self, name: &str) -> Option<usize> {
+    self.scopes.iter().rposition(|scope| scope.contains_key(name))
+  }
+
+  pub(crate) fn get_var(&self, name: &str) -> Result<WoojinVariable, WoojinError> {
+    match self.find_scope(name) {
+      Some(i) => Ok(self.scopes[i][name].clone()),
+      None => Err(WoojinError::new(format!("Variable {} is not declared", name), WoojinErrorKind::UndeclaredVariable).with_help(format!("declare it first, e.g. `let {} = 0`", name)))
+    }
+  }
+
+  pub(crate) fn change_var(&mut self, name: &str, value: &WoojinValue) -> Result<(), WoojinError> {
+    let scope: usize = match self.find_scope(name) {
+      Some(i) => i,
+      None => return Err(WoojinError::new(format!("Variable {} is not declared", name), WoojinErrorKind::UndeclaredVariable).with_help(format!("declare it first, e.g. `let mut {} = 0`", name)))
+    };
+    let var: WoojinVariable = self.scopes[scope][name].clone();
+    if !var.is_mut { return Err(WoojinError::new(format!("Variable {} is not mutable", name), WoojinErrorKind::VariableNotMutable).with_help(format!("declare it with `let mut {}` to allow changing it", name))); }
+    let value: WoojinValue = match value.coerce(var.kind) {
+      Some(value) => value,
+      None => return Err(WoojinError::new(format!("Variable {} is not {}", name, value.kind()), WoojinErrorKind::TypeMismatch))
+    };
+    self.scopes[scope].insert(name.to_string(), WoojinVariable {
+      value,
+      kind: var.kind,
+      is_mut: var.is_mut
+    });
+    Ok(())
+  }
+
+  /// Binds an immutable global that the interpreter provides, replacing any previous value.
+  pub(crate) fn define(&mut self, name: &str, value: WoojinValue) {
+    self.scopes[0].insert(name.to_string(), WoojinVariable { kind: value.kind(), value, is_mut: false });
+  }
+
+  pub(crate) fn dec_var(&mut self, name: &str, value: &WoojinValue, option: &VariableOption) -> Result<(), WoojinError> {
+    let scope: &mut Scope = self.scopes.last_mut().expect("the global scope is never popped");
+    if scope.contains_key(name) { return Err(WoojinError::new(format!("Variable {} is already declared", name), WoojinErrorKind::VariableAlreadyDeclared)); }
+    scope.insert(name.to_string(), WoojinVariable {
+      value: value.clone(),
+      kind: value.kind(),
+      is_mut: option.is_mut
+    });
+    Ok(())
+  }
+}
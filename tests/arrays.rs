@@ -0,0 +1,68 @@
+mod common;
+
+use woojin::{error::WoojinErrorKind, types::WoojinValue};
+use common::eval;
+
+#[test]
+fn arrays_are_compared_element_by_element() {
+  assert_eq!(eval("[1] == [1L]").unwrap(), WoojinValue::Bool(true));
+  assert_eq!(eval("[1, [2.0]] == [1.0d, [2L]]").unwrap(), WoojinValue::Bool(true));
+  assert_eq!(eval("[1, 2] != [1L, 2]").unwrap(), WoojinValue::Bool(false));
+  assert_eq!(eval("[1, 2] == [1, 3]").unwrap(), WoojinValue::Bool(false));
+  assert_eq!(eval("[1, 2] == [1]").unwrap(), WoojinValue::Bool(false));
+  assert_eq!(eval("[] == []").unwrap(), WoojinValue::Bool(true));
+  // Elements follow the rules of `==`, so a number and a string can't be compared.
+  assert_eq!(eval("[1] == [\"1\"]").unwrap_err().kind, WoojinErrorKind::CannotCompare);
+}
+
+fn ints(values: &[i32]) -> WoojinValue {
+  WoojinValue::Array(values.iter().map(|value| WoojinValue::Int(*value)).collect())
+}
+
+#[test]
+fn indexing_reads_elements_and_checks_the_bounds() {
+  assert_eq!(eval("let a = [10, 20, 30]\n$a[0] + $a[2]").unwrap(), WoojinValue::Int(40));
+  assert_eq!(eval("let a = [[1, 2], [3, 4]]\n$a[1][0]").unwrap(), WoojinValue::Int(3));
+  assert_eq!(eval("let a = [1, 2]\n$a[1L]").unwrap(), WoojinValue::Int(2));
+  for src in ["let a = [1, 2]\n$a[2]", "let a = [1, 2]\n$a[-1]", "[][0]", "let a = [[1]]\n$a[0][1]"] {
+    assert_eq!(eval(src).unwrap_err().kind, WoojinErrorKind::IndexOutOfBounds, "{:?}", src);
+  }
+  assert_eq!(eval("let a = [1]\n$a[\"0\"]").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+  assert_eq!(eval("let n = 1\n$n[0]").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+}
+
+#[test]
+fn index_assignment_changes_a_mutable_array() {
+  assert_eq!(eval("let mut a = [1, 2, 3]\n$a[1] = 5\n$a").unwrap(), ints(&[1, 5, 3]));
+  assert_eq!(eval("let mut a = [[1, 2], [3, 4]]\n$a[0][1] = 9\n$a").unwrap(), WoojinValue::Array(vec![ints(&[1, 9]), ints(&[3, 4])]));
+  // The assignment has the value that was assigned.
+  assert_eq!(eval("let mut a = [0]\n$a[0] = 7").unwrap(), WoojinValue::Int(7));
+  assert_eq!(eval("let mut a = [1]\n$a[1] = 2").unwrap_err().kind, WoojinErrorKind::IndexOutOfBounds);
+  assert_eq!(eval("let mut a = [[1]]\n$a[0][0][0] = 2").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+}
+
+#[test]
+fn index_assignment_needs_a_mutable_variable() {
+  let e = eval("let a = [1, 2]\n$a[0] = 5").unwrap_err();
+  assert_eq!(e.kind, WoojinErrorKind::VariableNotMutable);
+  assert_eq!(eval("let a = [[1]]\n$a[0][0] = 5").unwrap_err().kind, WoojinErrorKind::VariableNotMutable);
+  assert_eq!(eval("$missing[0] = 1").unwrap_err().kind, WoojinErrorKind::UndeclaredVariable);
+}
+
+#[test]
+fn plus_concatenates_arrays() {
+  assert_eq!(eval("[1, 2] + [3]").unwrap(), ints(&[1, 2, 3]));
+  assert_eq!(eval("[] + []").unwrap(), WoojinValue::Array(vec![]));
+  assert_eq!(eval("let a = [1]\nlet b = $a + $a\n[$a, $b]").unwrap(), WoojinValue::Array(vec![ints(&[1]), ints(&[1, 1])]));
+  assert_eq!(eval("[1] + 2").unwrap_err().kind, WoojinErrorKind::CannotAdd);
+}
+
+#[test]
+fn len_counts_elements_and_characters() {
+  assert_eq!(eval("len([1, [2, 3], 4])").unwrap(), WoojinValue::Int(3));
+  assert_eq!(eval("len([])").unwrap(), WoojinValue::Int(0));
+  assert_eq!(eval("len(\"héllo\")").unwrap(), WoojinValue::Int(5));
+  assert_eq!(eval("len(\"\")").unwrap(), WoojinValue::Int(0));
+  assert_eq!(eval("len(1)").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+  assert_eq!(eval("len([1], [2])").unwrap_err().kind, WoojinErrorKind::ArgumentMismatch);
+}
@@ -0,0 +1,21 @@
+use std::process::{Command, Output};
+
+fn woojin(args: &[&str]) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_woojin")).args(args).output().unwrap()
+}
+
+#[test]
+fn a_wrong_command_line_exits_with_2() {
+  for args in [&["--frobnicate"][..], &["-e"], &["run"], &["--explain"]] {
+    let output: Output = woojin(args);
+    assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Usage:"), "{:?}", args);
+  }
+}
+
+#[test]
+fn inline_code_gets_the_arguments_after_it() {
+  let output: Output = woojin(&["-e", "println $args", "--", "a", "b"]);
+  assert_eq!(output.status.code(), Some(0));
+  assert_eq!(String::from_utf8(output.stdout).unwrap().trim_end(), "[a, b]");
+}
@@ -0,0 +1,13 @@
+// Helpers shared by the test files. Each file is its own crate and uses only some of them.
+#![allow(dead_code)]
+
+use woojin::{Interpreter, error::WoojinError, types::WoojinValue};
+
+/// Runs `src` in a fresh interpreter.
+pub fn eval(src: &str) -> Result<WoojinValue, WoojinError> {
+  Interpreter::new().eval_str(src)
+}
+
+pub fn string(value: &str) -> WoojinValue {
+  WoojinValue::String(value.to_string())
+}
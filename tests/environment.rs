@@ -0,0 +1,65 @@
+mod common;
+
+use woojin::{Interpreter, error::{WoojinError, WoojinErrorKind}, types::WoojinValue};
+use common::{eval, string};
+
+#[test]
+fn args_are_an_array_of_strings() {
+  assert_eq!(eval("$args").unwrap(), WoojinValue::Array(vec![]));
+  let mut interpreter: Interpreter = Interpreter::new();
+  interpreter.set_args(vec!["first".to_string(), "second".to_string()]);
+  assert_eq!(interpreter.eval_str("[len($args), $args[1]]").unwrap(), WoojinValue::Array(vec![WoojinValue::Int(2), string("second")]));
+  assert_eq!(interpreter.eval_str("$args = []").unwrap_err().kind, WoojinErrorKind::VariableNotMutable);
+}
+
+/// An interpreter in which `WOOJIN_TEST_GREETING` is set to `hello`.
+fn greeting() -> Interpreter {
+  let mut interpreter: Interpreter = Interpreter::new();
+  interpreter.allow_env_write(true);
+  interpreter.eval_str("setenv \"WOOJIN_TEST_GREETING\", \"hello\"").unwrap();
+  interpreter
+}
+
+#[test]
+fn env_can_be_used_inside_expressions() {
+  assert_eq!(greeting().eval_str("env \"WOOJIN_TEST_GREETING\"").unwrap(), string("hello"));
+  assert_eq!(greeting().eval_str("\"say \" + env \"WOOJIN_TEST_GREETING\" + \"!\"").unwrap(), string("say hello!"));
+  assert_eq!(greeting().eval_str("let name = \"WOOJIN_TEST_GREETING\"\nif env $name == \"hello\":\n  yee 3").unwrap_err().exit_code(), Some(3));
+  assert_eq!(greeting().eval_str("len(env(\"WOOJIN_TEST_\" + \"GREETING\"))").unwrap(), WoojinValue::Int(5));
+  assert_eq!(eval("env \"WOOJIN_TEST_UNSET\" == \"\"").unwrap(), WoojinValue::Bool(true));
+  let path: String = std::env::var("PATH").unwrap_or_default();
+  assert_eq!(eval("env \"PATH\"").unwrap(), string(&path));
+}
+
+#[test]
+fn env_names_must_be_valid() {
+  for src in ["env 1", "env \"\"", "env \"A=B\"", "\"x\" + env [\"HOME\"]"] {
+    assert_eq!(eval(src).unwrap_err().kind, WoojinErrorKind::TypeMismatch, "{:?}", src);
+  }
+  let e: WoojinError = eval("println 1 + env 2").unwrap_err();
+  assert_eq!(e.span.map(|span| (span.column, span.len)), Some((17, 1)));
+}
+
+#[test]
+fn setenv_needs_permission() {
+  let e: WoojinError = eval("setenv \"WOOJIN_TEST_DENIED\", \"1\"").unwrap_err();
+  assert_eq!(e.kind, WoojinErrorKind::PermissionDenied);
+  assert!(e.help.is_some());
+  assert!(std::env::var_os("WOOJIN_TEST_DENIED").is_none());
+
+  let mut interpreter: Interpreter = Interpreter::new();
+  interpreter.allow_env_write(true);
+  assert_eq!(interpreter.eval_str("setenv \"WOOJIN_TEST_ALLOWED\", \"yes\"\nenv \"WOOJIN_TEST_ALLOWED\"").unwrap(), string("yes"));
+  assert_eq!(interpreter.eval_str("setenv \"WOOJIN_TEST_ALLOWED\", 1").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+}
+
+#[test]
+fn setenv_only_changes_the_interpreter_that_ran_it() {
+  let mut interpreter: Interpreter = greeting();
+  assert_eq!(Interpreter::new().eval_str("env \"WOOJIN_TEST_GREETING\"").unwrap(), string(""));
+  assert!(std::env::var_os("WOOJIN_TEST_GREETING").is_none());
+  // A variable of the process can be overridden too, without changing it for anyone else.
+  interpreter.eval_str("setenv \"PATH\", \"nowhere\"").unwrap();
+  assert_eq!(interpreter.eval_str("env \"PATH\"").unwrap(), string("nowhere"));
+  assert_ne!(std::env::var("PATH").ok().as_deref(), Some("nowhere"));
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use woojin::{Interpreter, error::{WoojinError, WoojinErrorKind}, types::WoojinValue};
+use common::eval;
+
+#[test]
+fn parameters_are_checked_against_their_types() {
+  let src: &str = "fn greet($name: string, $times: int):\n  return $name * $times\n";
+  assert_eq!(eval(&format!("{}greet(\"hi\", 2)", src)).unwrap(), WoojinValue::String("hihi".to_string()));
+  let e: WoojinError = eval(&format!("{}greet(2, \"hi\")", src)).unwrap_err();
+  assert_eq!(e.kind, WoojinErrorKind::TypeMismatch);
+  assert_eq!(e.details, "Parameter name of function greet must be string, not int");
+}
+
+#[test]
+fn calls_need_one_argument_per_parameter() {
+  let src: &str = "fn add($a, $b):\n  return $a + $b\n";
+  assert_eq!(eval(&format!("{}add(1, 2)", src)).unwrap(), WoojinValue::Int(3));
+  assert_eq!(eval(&format!("{}add(1)", src)).unwrap_err().kind, WoojinErrorKind::ArgumentMismatch);
+  assert_eq!(eval(&format!("{}add(1, 2, 3)", src)).unwrap_err().kind, WoojinErrorKind::ArgumentMismatch);
+  assert_eq!(eval("missing(1)").unwrap_err().kind, WoojinErrorKind::UndeclaredFunction);
+}
+
+#[test]
+fn return_without_a_value_stops_the_function() {
+  let src: &str = "\
+let mut calls = 0
+fn log($n):
+  $calls = $calls + 1
+  if $n > 0:
+    return
+  $calls = 100
+log(1)
+[log(1), $calls]";
+  assert_eq!(eval(src).unwrap(), WoojinValue::Array(vec![WoojinValue::Unit, WoojinValue::Int(2)]));
+  assert_eq!(eval("return 1").unwrap_err().kind, WoojinErrorKind::ReturnOutsideFunction);
+}
+
+#[test]
+fn break_does_not_leave_the_function_it_is_in() {
+  let src: &str = "\
+fn stop():
+  break
+for $i in 0..3:
+  stop()";
+  assert_eq!(eval(src).unwrap_err().kind, WoojinErrorKind::LoopControlOutsideLoop);
+  // A loop inside the function is fine.
+  assert_eq!(eval("fn first():\n  for $i in 5..9:\n    break\n  return 1\nfirst()").unwrap(), WoojinValue::Int(1));
+}
+
+#[test]
+fn functions_only_see_globals_and_their_own_variables() {
+  let src: &str = "\
+let global = 1
+fn peek():
+  return $local
+if uglyguri:
+  let local = 2
+  peek()";
+  assert_eq!(eval(src).unwrap_err().kind, WoojinErrorKind::UndeclaredVariable);
+  assert_eq!(eval("let global = 1\nfn peek():\n  return $global\npeek()").unwrap(), WoojinValue::Int(1));
+}
+
+#[test]
+fn recursion_up_to_the_limit_works_from_any_thread() {
+  // The body is nested a few blocks deep, so every call takes more stack than a bare `return`.
+  let src: &str = "\
+fn dig($n: int):
+  let mut result = 0
+  for $i in 0..1:
+    while uglyguri:
+      if $n > 1:
+        $result = 1 + dig($n - 1)
+      else:
+        $result = 1
+      break
+  return $result
+dig(N)";
+  // A spawned thread has a stack of 2 MiB, which is too small for these calls on its own.
+  let run = |limit: Option<usize>, depth: usize| {
+    let src: String = src.replace('N', &depth.to_string());
+    std::thread::spawn(move || {
+      let mut interpreter: Interpreter = Interpreter::new();
+      if let Some(limit) = limit { interpreter.set_max_call_depth(limit); }
+      interpreter.eval_str(&src)
+    }).join().unwrap()
+  };
+  assert_eq!(run(None, 1000).unwrap(), WoojinValue::Int(1000));
+  assert_eq!(run(None, 1001).unwrap_err().kind, WoojinErrorKind::StackOverflow);
+  assert_eq!(run(Some(10), 10).unwrap(), WoojinValue::Int(10));
+  assert_eq!(run(Some(10), 11).unwrap_err().kind, WoojinErrorKind::StackOverflow);
+  assert_eq!(run(Some(3000), 3000).unwrap(), WoojinValue::Int(3000));
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use woojin::{error::{WoojinError, WoojinErrorKind}, types::WoojinValue};
+use common::eval;
+
+#[test]
+fn logical_operators_short_circuit() {
+  assert_eq!(eval("beautifulguri and missing()").unwrap(), WoojinValue::Bool(false));
+  assert_eq!(eval("uglyguri or missing()").unwrap(), WoojinValue::Bool(true));
+  assert_eq!(eval("uglyguri and missing()").unwrap_err().kind, WoojinErrorKind::UndeclaredFunction);
+}
+
+#[test]
+fn operands_that_are_not_booleans_are_pointed_at() {
+  for (src, column, len) in [("uglyguri and 1", 14, 1), ("1 or uglyguri", 1, 1), ("not \"yes\"", 5, 5)] {
+    let e: WoojinError = eval(src).unwrap_err();
+    assert_eq!(e.kind, WoojinErrorKind::TypeMismatch, "{:?}", src);
+    let span = e.span.unwrap();
+    assert_eq!((span.column, span.len), (column, len), "{:?}", src);
+  }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use woojin::{error::{WoojinError, WoojinErrorKind}, types::WoojinValue};
+use common::eval;
+
+#[test]
+fn break_and_continue_work_inside_nested_ifs() {
+  let src: &str = "\
+let mut i = 0
+let mut total = 0
+while uglyguri:
+  $i = $i + 1
+  if $i > 2:
+    if $i % 2 == 0:
+      continue
+    if $i > 7:
+      break
+  $total = $total + $i
+[$i, $total]";
+  // 1 + 2 + 3 + 5 + 7, and the loop stops at 9.
+  assert_eq!(eval(src).unwrap(), WoojinValue::Array(vec![WoojinValue::Int(9), WoojinValue::Int(18)]));
+}
+
+#[test]
+fn break_only_leaves_the_innermost_loop() {
+  let src: &str = "\
+let mut count = 0
+let mut i = 0
+while $i < 3:
+  $i = $i + 1
+  let mut j = 0
+  while uglyguri:
+    $j = $j + 1
+    if $j == 2:
+      break
+    $count = $count + 1
+$count";
+  assert_eq!(eval(src).unwrap(), WoojinValue::Int(3));
+}
+
+#[test]
+fn loop_control_outside_a_loop_is_an_error() {
+  for src in ["break", "continue", "if uglyguri:\n  break"] {
+    let e: WoojinError = eval(src).unwrap_err();
+    assert_eq!(e.kind, WoojinErrorKind::LoopControlOutsideLoop, "{:?}", src);
+    assert_eq!(e.kind.code(), Some(24));
+  }
+}
+
+#[test]
+fn ranges_are_not_collected_before_the_loop() {
+  // Two hundred million elements would take gigabytes if they were built up front.
+  let src: &str = "\
+let mut last = 0L
+for $i in 0..200000000:
+  $last = $i
+  if $i == 3:
+    break
+$last";
+  assert_eq!(eval(src).unwrap(), WoojinValue::Long(3));
+  assert_eq!(eval(&src.replace("200000000", "9000000000000000000L")).unwrap(), WoojinValue::Long(3));
+}
+
+#[test]
+fn ranges_with_a_long_bound_count_in_longs() {
+  assert_eq!(eval("let mut n = 0L\nfor $i in 1..3L:\n  $n = $n + $i\n$n").unwrap(), WoojinValue::Long(3));
+  assert_eq!(eval("for $i in 0..1.5:\n  println $i").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+}
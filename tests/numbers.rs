@@ -0,0 +1,55 @@
+mod common;
+
+use woojin::{error::WoojinErrorKind, types::WoojinValue};
+use common::eval;
+
+#[test]
+fn sleep_takes_a_non_negative_int_or_long() {
+  assert_eq!(eval("sleep 0\nsleep 1L\n1").unwrap(), WoojinValue::Int(1));
+  for src in ["sleep -1", "sleep -1L", "sleep 0 - 5", "sleep 1.5"] {
+    assert_eq!(eval(src).unwrap_err().kind, WoojinErrorKind::TypeMismatch, "{:?}", src);
+  }
+}
+
+fn overflows(src: &str) {
+  match eval(src) {
+    Err(e) => assert_eq!(e.kind, WoojinErrorKind::IntegerOverflow, "{:?}: {}", src, e),
+    Ok(value) => panic!("{:?} gave {:?}", src, value),
+  }
+}
+
+#[test]
+fn int_arithmetic_that_overflows_is_an_error() {
+  overflows("2147483647 + 1");
+  overflows("-2147483648 - 1");
+  overflows("65536 * 65536");
+  overflows("-2147483648 / -1");
+  overflows("2 ** 31");
+  overflows("-(-2147483648)");
+  assert_eq!(eval("2147483646 + 1").unwrap(), WoojinValue::Int(i32::MAX));
+  assert_eq!(eval("-2147483648 / 1").unwrap(), WoojinValue::Int(i32::MIN));
+}
+
+#[test]
+fn long_arithmetic_that_overflows_is_an_error() {
+  overflows("9223372036854775807L + 1");
+  overflows("-9223372036854775808L - 1");
+  overflows("4294967296L * 4294967296L");
+  overflows("-9223372036854775808L / -1");
+  overflows("2L ** 63");
+  overflows("2L ** 9999999999L");
+  // An int mixed with a long is widened first, so this doesn't overflow.
+  assert_eq!(eval("2147483647 + 1L").unwrap(), WoojinValue::Long(2147483648));
+}
+
+#[test]
+fn strings_are_repeated_a_sensible_number_of_times() {
+  assert_eq!(eval("\"ab\" * 3").unwrap(), WoojinValue::String("ababab".to_string()));
+  assert_eq!(eval("\"ab\" * 0L").unwrap(), WoojinValue::String(String::new()));
+  assert_eq!(eval("\"\" * 9223372036854775807L").unwrap(), WoojinValue::String(String::new()));
+  assert_eq!(eval("\"ab\" * -1").unwrap_err().kind, WoojinErrorKind::CannotMultiply);
+  overflows("\"ab\" * 9223372036854775807L");
+  overflows("\"abcd\" * 4611686018427387904L");
+  // Fits in the size of a string, but not in memory.
+  assert_eq!(eval("\"ab\" * 4611686018427387903L").unwrap_err().kind, WoojinErrorKind::CannotMultiply);
+}
@@ -0,0 +1,112 @@
+use woojin::{Interpreter, error::WoojinErrorKind, types::WoojinValue};
+
+fn eval(src: &str) -> WoojinValue {
+  Interpreter::new().eval_str(src).unwrap_or_else(|e| panic!("{}: {}", src, e))
+}
+
+// Each expression evaluates differently depending on how it is grouped,
+// so the results pin down the shape of the parsed tree.
+
+#[test]
+fn comparison_binds_looser_than_arithmetic() {
+  assert_eq!(eval("1 + 2 > 2"), WoojinValue::Bool(true));
+  assert_eq!(eval("2 * 3 == 6"), WoojinValue::Bool(true));
+  assert_eq!(eval("10 - 1 <= 3 * 3"), WoojinValue::Bool(true));
+  assert_eq!(eval("1 + 1 = 2"), WoojinValue::Bool(true));
+  assert_eq!(eval("1 + 1 != 2"), WoojinValue::Bool(false));
+}
+
+#[test]
+fn product_binds_tighter_than_sum() {
+  assert_eq!(eval("2 + 3 * 4"), WoojinValue::Int(14));
+  assert_eq!(eval("2 * 3 + 4"), WoojinValue::Int(10));
+  assert_eq!(eval("(2 + 3) * 4"), WoojinValue::Int(20));
+  assert_eq!(eval("7 - 6 % 4"), WoojinValue::Int(5));
+  assert_eq!(eval("1 + 7 // 2"), WoojinValue::Int(4));
+}
+
+#[test]
+fn binary_operators_are_left_associative() {
+  assert_eq!(eval("10 - 4 - 3"), WoojinValue::Int(3));
+  assert_eq!(eval("100 / 10 / 5"), WoojinValue::Int(2));
+  assert_eq!(eval("2 * 6 % 5"), WoojinValue::Int(2));
+}
+
+#[test]
+fn power_is_right_associative_and_binds_tightest() {
+  assert_eq!(eval("2 ** 3 ** 2"), WoojinValue::Int(512));
+  assert_eq!(eval("2 * 3 ** 2"), WoojinValue::Int(18));
+  assert_eq!(eval("-2 ** 2"), WoojinValue::Int(-4));
+  assert_eq!(eval("(-2) ** 2"), WoojinValue::Int(4));
+  assert_eq!(eval("2 ** -1"), WoojinValue::Double(0.5));
+}
+
+#[test]
+fn unary_minus() {
+  assert_eq!(eval("-2147483648"), WoojinValue::Int(i32::MIN));
+  assert_eq!(eval("-(1 + 2) * 2"), WoojinValue::Int(-6));
+  assert_eq!(eval("3 - -2"), WoojinValue::Int(5));
+  assert_eq!(eval("--3"), WoojinValue::Int(3));
+}
+
+#[test]
+fn unary_minus_negates_every_kind_of_number_and_variables() {
+  assert_eq!(eval("let x = 5\n-$x"), WoojinValue::Int(-5));
+  assert_eq!(eval("let x = -5\n-$x * 2"), WoojinValue::Int(10));
+  assert_eq!(eval("let x = 3L\n-$x"), WoojinValue::Long(-3));
+  assert_eq!(eval("-9000000000L"), WoojinValue::Long(-9000000000));
+  assert_eq!(eval("let x = 1.5\n-$x"), WoojinValue::Float(-1.5));
+  assert_eq!(eval("-(2.5d)"), WoojinValue::Double(-2.5));
+  assert_eq!(eval("let x = 0.25d\n1 - -$x"), WoojinValue::Double(1.25));
+  for src in ["-\"s\"", "let s = \"s\"\n-$s", "-uglyguri", "-[1]"] {
+    assert_eq!(Interpreter::new().eval_str(src).unwrap_err().kind, WoojinErrorKind::CannotSubtract, "{:?}", src);
+  }
+  assert_eq!(Interpreter::new().eval_str("let x = -2147483648\n-$x").unwrap_err().kind, WoojinErrorKind::IntegerOverflow);
+}
+
+#[test]
+fn logical_operators_bind_looser_than_comparison() {
+  assert_eq!(eval("1 < 2 and 2 < 3"), WoojinValue::Bool(true));
+  assert_eq!(eval("not 1 == 2"), WoojinValue::Bool(true));
+  assert_eq!(eval("!uglyguri == beautifulguri"), WoojinValue::Bool(true));
+  assert_eq!(eval("uglyguri or beautifulguri and beautifulguri"), WoojinValue::Bool(true));
+  assert_eq!(eval("(uglyguri or beautifulguri) and beautifulguri"), WoojinValue::Bool(false));
+  assert_eq!(eval("not beautifulguri and beautifulguri"), WoojinValue::Bool(false));
+}
+
+#[test]
+fn indexing_binds_tighter_than_everything_else() {
+  assert_eq!(eval("-[1, 2][1]"), WoojinValue::Int(-2));
+  assert_eq!(eval("[2, 3][0] ** 2"), WoojinValue::Int(4));
+  assert_eq!(eval("[[1, 2], [3, 4]][1][0] + 1"), WoojinValue::Int(4));
+}
+
+// The sign of `%` follows the divisor and `//` rounds down, so a == (a // b) * b + a % b.
+#[test]
+fn remainder_and_floor_division_with_negative_operands() {
+  assert_eq!(eval("-7 % 3"), WoojinValue::Int(2));
+  assert_eq!(eval("7 % -3"), WoojinValue::Int(-2));
+  assert_eq!(eval("-7 % -3"), WoojinValue::Int(-1));
+  assert_eq!(eval("-6 % 3"), WoojinValue::Int(0));
+  assert_eq!(eval("-7 // 2"), WoojinValue::Int(-4));
+  assert_eq!(eval("7 // -2"), WoojinValue::Int(-4));
+  assert_eq!(eval("-7 // -2"), WoojinValue::Int(3));
+  assert_eq!(eval("-8 // 2"), WoojinValue::Int(-4));
+  assert_eq!(eval("-7L % 3L"), WoojinValue::Long(2));
+  assert_eq!(eval("-7L // 2L"), WoojinValue::Long(-4));
+  assert_eq!(eval("-7.5d % 2.0d"), WoojinValue::Double(0.5));
+  assert_eq!(eval("-7.5d // 2.0d"), WoojinValue::Double(-4.0));
+  for (a, b) in [(-7, 3), (7, -3), (-7, -3), (7, 3)] {
+    assert_eq!(eval(&format!("({a} // {b}) * {b} + {a} % {b}")), WoojinValue::Int(a), "{} and {}", a, b);
+  }
+}
+
+#[test]
+fn dividing_by_zero_is_an_error() {
+  for src in ["1 / 0", "1 % 0", "1 // 0", "1L % 0L", "1L // 0", "1.5 % 0.0", "1.5d // 0.0d", "0 ** -1", "0L ** -2L", "0.0 ** -1.0", "0.0d ** -0.5d"] {
+    let e = Interpreter::new().eval_str(src).unwrap_err();
+    assert_eq!(e.kind, WoojinErrorKind::DivisionByZero, "{:?}", src);
+  }
+  assert_eq!(eval("0 ** 0"), WoojinValue::Int(1));
+  assert_eq!(eval("0 ** 2"), WoojinValue::Int(0));
+}
@@ -0,0 +1,113 @@
+use woojin::{Interpreter, error::{WoojinError, WoojinErrorKind}};
+
+#[test]
+fn errors_in_functions_quote_the_code_the_function_was_loaded_from() {
+  let mut interpreter: Interpreter = Interpreter::new();
+  interpreter.eval_str("fn f($n):\n  let zero = 0\n  return $n / $zero").unwrap();
+  let e: WoojinError = interpreter.eval_str("if uglyguri:\n  println 1\n  println f(1)").unwrap_err();
+  assert_eq!(e.kind, WoojinErrorKind::DivisionByZero);
+  assert_eq!(e.span.map(|span| span.line), Some(3));
+  assert_eq!(e.source_line.as_deref(), Some("  return $n / $zero"));
+}
+
+#[test]
+fn errors_at_the_call_quote_the_calling_code() {
+  let mut interpreter: Interpreter = Interpreter::new();
+  interpreter.eval_str("fn f($n: int):\n  return $n").unwrap();
+  let e: WoojinError = interpreter.eval_str("println 1\nprintln f(\"one\")").unwrap_err();
+  assert_eq!(e.kind, WoojinErrorKind::TypeMismatch);
+  assert_eq!(e.source_line.as_deref(), Some("println f(\"one\")"));
+  assert!(e.to_string().contains("2 | println f(\"one\")"), "{}", e);
+}
+
+#[test]
+fn every_parse_error_is_reported_at_once() {
+  let src: &str = "\
+let a = 1 +
+println $a
+if $a ==:
+  println (
+let b = 2
+println $b $b
+fn broken(:
+  return 1
+let c = 3";
+  let errors: Vec<WoojinError> = Interpreter::new().load_str(src).unwrap_err();
+  let lines: Vec<usize> = errors.iter().map(|e| e.span.unwrap().line).collect();
+  // The block of the broken `if` is skipped with it, so line 4 isn't reported on its own.
+  assert_eq!(lines, vec![1, 3, 6, 7]);
+  assert!(errors.iter().all(|e| e.kind == WoojinErrorKind::ParseError));
+  assert!(errors.iter().all(|e| e.source_line.is_some()));
+}
+
+#[test]
+fn exit_has_no_error_code() {
+  let e: WoojinError = Interpreter::new().eval_str("yee 3").unwrap_err();
+  assert_eq!(e.kind.code(), None);
+  assert_eq!(e.exit_code(), Some(3));
+  assert!(!e.to_string().contains("WJ"), "{}", e);
+  assert_eq!(WoojinErrorKind::from_code("WJ3"), Some(WoojinErrorKind::UnknownToken));
+}
+
+#[test]
+fn every_error_has_a_unique_code_and_an_explanation() {
+  // Numbers every kind in the order it is declared in. Adding a kind breaks this match,
+  // and the asserts below then fail until the new kind is in `ALL` too.
+  let ordinal = |kind: WoojinErrorKind| -> usize {
+    use WoojinErrorKind::*;
+    match kind {
+      Roar => 0, Success => 1, Unknown => 2, UnknownToken => 3, FileNotFound => 4,
+      UnsupportedExtension => 5, FailReadFailure => 6, UndeclaredVariable => 7,
+      VariableAlreadyDeclared => 8, VariableNotMutable => 9, ParseError => 10, CannotAdd => 11,
+      CannotSubtract => 12, CannotMultiply => 13, CannotDivide => 14, DivisionByZero => 15,
+      InvalidType => 16, TypeMismatch => 17, InvalidIndent => 18, IfParsingFailed => 19,
+      ElseParsingFailed => 20, InvaildAssignment => 21, CannotCompare => 22,
+      WhileParsingFailed => 23, LoopControlOutsideLoop => 24, ForParsingFailed => 25,
+      FunctionParsingFailed => 26, UndeclaredFunction => 27, ArgumentMismatch => 28,
+      ReturnOutsideFunction => 29, StackOverflow => 30, IndexOutOfBounds => 31,
+      IntegerOverflow => 32, CannotExponentiate => 33, TryParsingFailed => 34,
+      PermissionDenied => 35, Exit(_) => 36,
+    }
+  };
+  let errors: usize = ordinal(WoojinErrorKind::Exit(0));
+  assert_eq!(WoojinErrorKind::ALL.len(), errors);
+  let mut seen: Vec<usize> = WoojinErrorKind::ALL.iter().map(|kind| ordinal(*kind)).collect();
+  seen.sort();
+  assert_eq!(seen, (0..errors).collect::<Vec<usize>>());
+
+  let mut codes: Vec<i32> = WoojinErrorKind::ALL.iter().map(|kind| kind.code().unwrap()).collect();
+  codes.sort();
+  codes.dedup();
+  assert_eq!(codes.len(), errors);
+  for kind in WoojinErrorKind::ALL {
+    assert!(kind.explain().is_some_and(|text| !text.trim().is_empty()), "{:?} has no explanation", kind);
+    assert_eq!(WoojinErrorKind::from_code(&format!("WJ{}", kind.code().unwrap())), Some(kind));
+  }
+}
+
+#[test]
+fn lexer_and_parser_errors_are_reported_together_in_order() {
+  let src: &str = "\
+let a = 1 +
+println \"oops
+let b = @
+if uglyguri:
+  println \"never closed
+else:
+  println 1 2
+println 3 3";
+  let errors: Vec<WoojinError> = Interpreter::new().load_str(src).unwrap_err();
+  let found: Vec<(usize, WoojinErrorKind)> = errors.iter().map(|e| (e.span.unwrap().line, e.kind)).collect();
+  assert_eq!(found, vec![
+    (1, WoojinErrorKind::ParseError),
+    (2, WoojinErrorKind::ParseError),
+    (3, WoojinErrorKind::UnknownToken),
+    (5, WoojinErrorKind::ParseError),
+    (7, WoojinErrorKind::ParseError),
+    (8, WoojinErrorKind::ParseError),
+  ]);
+  // A block whose only line failed to lex is not reported as missing as well.
+  let errors: Vec<WoojinError> = Interpreter::new().load_str("if uglyguri:\n  println 1\nif uglyguri:\n\tprintln 2").unwrap_err();
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].kind, WoojinErrorKind::InvalidIndent);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use std::process::{Command, Output};
+
+use woojin::{error::WoojinErrorKind, types::WoojinValue};
+use common::eval;
+
+/// Runs `src` with the woojin binary, from a file named after the test.
+fn run(name: &str, src: &str) -> Output {
+  let path = std::env::temp_dir().join(format!("woojin-roar-{}-{}.wj", name, std::process::id()));
+  std::fs::write(&path, src).unwrap();
+  let output: Output = Command::new(env!("CARGO_BIN_EXE_woojin")).arg(&path).output().unwrap();
+  std::fs::remove_file(&path).unwrap();
+  output
+}
+
+#[test]
+fn roar_stops_the_program() {
+  let e = eval("let mut reached = 1\nroar \"boom\"\n$reached = 2").unwrap_err();
+  assert_eq!(e.kind, WoojinErrorKind::Roar);
+  assert_eq!(e.details, "boom");
+  assert_eq!(e.status, None);
+  assert_eq!(e.exit_code(), None);
+}
+
+#[test]
+fn roar_takes_an_optional_exit_code() {
+  let e = eval("roar \"bad input, try again\", 3").unwrap_err();
+  assert_eq!(e.details, "bad input, try again");
+  assert_eq!(e.status, Some(3));
+  assert_eq!(eval("roar \"boom\", 0").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+  assert_eq!(eval("roar \"boom\", \"3\"").unwrap_err().kind, WoojinErrorKind::TypeMismatch);
+}
+
+#[test]
+fn roar_exits_with_its_code_and_message() {
+  let output: Output = run("code", "println \"before\"\nroar \"boom\", 42\nprintln \"after\"\n");
+  let stdout: String = String::from_utf8(output.stdout).unwrap();
+  assert_eq!(output.status.code(), Some(42));
+  assert!(stdout.starts_with("before\n"));
+  assert!(stdout.contains("boom"));
+  assert!(!stdout.contains("after"));
+
+  assert_eq!(run("default", "roar \"boom\"\n").status.code(), Some(1));
+}
+
+#[test]
+fn roar_is_caught_by_an_enclosing_try() {
+  let src: &str = "\
+let mut caught = \"nothing\"
+try:
+  roar \"boom\", 5
+catch $err:
+  $caught = $err.kind + \": \" + $err.message
+$caught";
+  assert_eq!(eval(src).unwrap(), WoojinValue::String("Roar: boom".to_string()));
+}
+
+#[test]
+fn roar_propagates_out_of_functions_to_the_caller() {
+  let src: &str = "\
+fn check($n: int):
+  if $n > 2:
+    roar \"too big\"
+  return $n
+let mut total = 0
+for $i in 0..5:
+  try:
+    $total = $total + check($i)
+  catch $err:
+    break
+$total";
+  assert_eq!(eval(src).unwrap(), WoojinValue::Int(3));
+}
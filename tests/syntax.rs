@@ -0,0 +1,155 @@
+mod common;
+
+use woojin::{Interpreter, error::{WoojinError, WoojinErrorKind}, types::WoojinValue};
+use common::{eval, string};
+
+#[test]
+fn words_that_start_with_a_keyword_are_not_keywords() {
+  let src: &str = "\
+fn forever($n):
+  return $n * 2
+fn trying():
+  return 1
+let printer = forever(20) + trying()
+$printer";
+  assert_eq!(eval(src).unwrap(), WoojinValue::Int(41));
+  assert_eq!(eval("printer = 1").unwrap_err().kind, WoojinErrorKind::UnknownToken);
+}
+
+#[test]
+fn strings_keep_escaped_quotes_and_commas() {
+  assert_eq!(eval("\"a\\\",b\"").unwrap(), string("a\",b"));
+  assert_eq!(eval("\"tab\\there\\\\\"").unwrap(), string("tab\there\\"));
+  assert_eq!(eval("let mut s = \"x\"\n$s = \"\"\n$s").unwrap(), string(""));
+  assert_eq!(eval("\"\" + \"\" == \"\"").unwrap(), WoojinValue::Bool(true));
+}
+
+#[test]
+fn malformed_code_is_an_error_instead_of_being_ignored() {
+  assert_eq!(eval("println \"oops").unwrap_err().kind, WoojinErrorKind::ParseError);
+  assert_eq!(eval("println \"\\q\"").unwrap_err().kind, WoojinErrorKind::ParseError);
+  assert_eq!(eval("println 1 2").unwrap_err().kind, WoojinErrorKind::ParseError);
+  assert_eq!(eval("let a = 1\n  println $a").unwrap_err().kind, WoojinErrorKind::InvalidIndent);
+}
+
+#[test]
+fn errors_point_at_the_token() {
+  let e: WoojinError = eval("let total = (1 + 2").unwrap_err();
+  let span = e.span.unwrap();
+  assert_eq!((span.line, span.column), (1, 19));
+  let e: WoojinError = eval("let a = 1\nprintln $a + $b").unwrap_err();
+  let span = e.span.unwrap();
+  assert_eq!((span.line, span.column, span.len), (2, 14, 2));
+}
+
+#[test]
+fn blocks_can_be_indented_with_tabs_or_any_number_of_spaces() {
+  let src: &str = "let mut n = 0\nfor $i in 0..3:\n{0}if $i > 0:\n{0}{0}$n = $n + $i\n$n";
+  for unit in ["  ", "    ", "\t"] {
+    assert_eq!(eval(&src.replace("{0}", unit)).unwrap(), WoojinValue::Int(3), "indented with {:?}", unit);
+  }
+}
+
+#[test]
+fn comment_lines_do_not_affect_indentation() {
+  let src: &str = "\
+let mut n = 0
+if uglyguri:
+  $n = 1
+      // a comment anywhere
+// another one
+else:
+  $n = 2
+$n";
+  assert_eq!(eval(src).unwrap(), WoojinValue::Int(1));
+}
+
+#[test]
+fn inconsistent_indentation_is_reported_where_it_happens() {
+  for src in [
+    "if uglyguri:\n    println 1\n  println 2",
+    "if uglyguri:\n  println 1\nif uglyguri:\n\tprintln 2",
+    "if uglyguri:\n \tprintln 1",
+    "if uglyguri:\n    println 1\nif uglyguri:\n        println 2",
+  ] {
+    let e: WoojinError = eval(src).unwrap_err();
+    assert_eq!(e.kind, WoojinErrorKind::InvalidIndent, "{:?}", src);
+    assert_eq!(e.span.map(|span| (span.line, span.column)), Some((src.lines().count(), 1)), "{:?}", src);
+  }
+}
+
+#[test]
+fn semicolons_separate_statements_on_one_line() {
+  assert_eq!(eval("let a = 1; let b = 2; $a + $b").unwrap(), WoojinValue::Int(3));
+  assert_eq!(eval("let mut n = 0;\nfor $i in 0..4:\n  if $i == 2:\n    continue;\n  $n = $n + $i; $n = $n * 2;\n$n;").unwrap(), WoojinValue::Int(10));
+  assert_eq!(eval("fn f($x):\n  return $x + 1;\nf(1)").unwrap(), WoojinValue::Int(2));
+  assert_eq!(eval("let a = 1;; let b = 2").unwrap_err().kind, WoojinErrorKind::ParseError);
+  assert_eq!(eval("println 1 println 2").unwrap_err().kind, WoojinErrorKind::ParseError);
+}
+
+#[test]
+fn nesting_too_deep_is_an_error_instead_of_a_stack_overflow() {
+  let blocks = |depth: usize| (0..depth).map(|i| format!("{}if uglyguri:\n", "  ".repeat(i))).collect::<String>() + &"  ".repeat(depth) + "1";
+  let deep: Vec<String> = vec![
+    format!("{}1{}", "(".repeat(100000), ")".repeat(100000)),
+    format!("{}uglyguri", "not ".repeat(100000)),
+    format!("{}1", "- ".repeat(100000)),
+    format!("{}1{}", "[".repeat(100000), "]".repeat(100000)),
+    blocks(1000),
+  ];
+  // Run on a thread of its own, with the default stack of 2 MiB.
+  let kinds: Vec<WoojinErrorKind> = std::thread::spawn(move || {
+    deep.iter().map(|src| Interpreter::new().eval_str(src).unwrap_err().kind).collect()
+  }).join().unwrap();
+  assert_eq!(kinds, vec![WoojinErrorKind::ParseError; 5]);
+
+  let shallow: String = format!("{}1{}", "(".repeat(32), ")".repeat(32));
+  assert_eq!(std::thread::spawn(move || eval(&shallow).unwrap()).join().unwrap(), WoojinValue::Int(1));
+  assert_eq!(eval(&blocks(31)).unwrap(), WoojinValue::Unit);
+}
+
+#[test]
+fn block_headers_may_end_in_a_semicolon() {
+  let src: &str = "\
+let a = 2;
+let mut n = 0;
+if $a == 1:;
+  $n = 1;
+else:;
+  $n = 2;
+for $i in 0..3:;
+  $n = $n + $i;
+fn f($x):;
+  return $x;
+try:;
+  $n = $n / 0;
+catch $e:;
+  $n = f($n) + 1;
+$n";
+  assert_eq!(eval(src).unwrap(), WoojinValue::Int(6));
+  assert_eq!(eval("if uglyguri:;;\n  println 1").unwrap_err().kind, WoojinErrorKind::ParseError);
+  assert_eq!(eval("if uglyguri:; println 1\n  println 1").unwrap_err().kind, WoojinErrorKind::ParseError);
+}
+
+#[test]
+fn long_chains_of_operators_do_not_count_as_nesting() {
+  let chains: Vec<(String, WoojinValue)> = vec![
+    (format!("1{}", " + 1".repeat(100000)), WoojinValue::Int(100001)),
+    (format!("\"\"{}", " + \"ab\"".repeat(1000)), string(&"ab".repeat(1000))),
+    (format!("2{}", " * 1 // 1 % 3".repeat(1000)), WoojinValue::Int(2)),
+    (format!("uglyguri{}", " and 1 < 2".repeat(1000)), WoojinValue::Bool(true)),
+    (format!("beautifulguri{}", " or beautifulguri".repeat(1000)), WoojinValue::Bool(false)),
+  ];
+  // Run on a thread of its own, with the default stack of 2 MiB.
+  let values: Vec<WoojinValue> = {
+    let sources: Vec<String> = chains.iter().map(|(src, _)| src.clone()).collect();
+    std::thread::spawn(move || sources.iter().map(|src| Interpreter::new().eval_str(src).unwrap()).collect()).join().unwrap()
+  };
+  assert_eq!(values, chains.into_iter().map(|(_, value)| value).collect::<Vec<WoojinValue>>());
+
+  // A chain inside the deepest blocks allowed.
+  let mut src: String = String::from("let mut n = 0\n");
+  for i in 0..31 { src += &format!("{}if uglyguri:\n", "  ".repeat(i)); }
+  src += &format!("{}$n = 1{}\n$n", "  ".repeat(31), " + 1".repeat(40));
+  assert_eq!(eval(&src).unwrap(), WoojinValue::Int(41));
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use woojin::{Interpreter, error::WoojinErrorKind, types::WoojinValue};
+use common::eval;
+
+#[test]
+fn interpreters_keep_their_own_variables() {
+  let mut first: Interpreter = Interpreter::new();
+  let mut second: Interpreter = Interpreter::new();
+  first.eval_str("let name = \"first\"").unwrap();
+  second.eval_str("let name = \"second\"").unwrap();
+  assert_eq!(first.eval_str("$name").unwrap(), WoojinValue::String("first".to_string()));
+  assert_eq!(second.eval_str("$name").unwrap(), WoojinValue::String("second".to_string()));
+  // A name declared twice in the same interpreter is still an error.
+  assert_eq!(first.eval_str("let name = 1").unwrap_err().kind, WoojinErrorKind::VariableAlreadyDeclared);
+}
+
+#[test]
+fn interpreters_on_different_threads_declare_the_same_name() {
+  let threads: Vec<std::thread::JoinHandle<WoojinValue>> = (0..2).map(|i| std::thread::spawn(move || {
+    let src: String = format!("let mut n = {}\nfor $i in 0..1000:\n  $n = $n + 1\n$n", i * 1000);
+    Interpreter::new().eval_str(&src).unwrap()
+  })).collect();
+  let results: Vec<WoojinValue> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+  assert_eq!(results, vec![WoojinValue::Int(1000), WoojinValue::Int(2000)]);
+}
+
+#[test]
+fn undeclared_variables_are_an_error() {
+  assert_eq!(eval("$missing").unwrap_err().kind, WoojinErrorKind::UndeclaredVariable);
+  assert_eq!(eval("let a = 1\n$a = 2").unwrap_err().kind, WoojinErrorKind::VariableNotMutable);
+}
+
+#[test]
+fn block_bindings_are_dropped_at_the_end_of_the_block() {
+  let src: &str = "\
+if uglyguri:
+  let inner = 1
+$inner";
+  assert_eq!(eval(src).unwrap_err().kind, WoojinErrorKind::UndeclaredVariable);
+  // The name is free again, so it can be declared at the top level.
+  assert_eq!(eval("if uglyguri:\n  let inner = 1\nlet inner = 2\n$inner").unwrap(), WoojinValue::Int(2));
+}
+
+#[test]
+fn shadowed_bindings_are_restored_after_the_block() {
+  let src: &str = "\
+let mut seen = \"\"
+let x = 1
+if uglyguri:
+  let x = \"inner\"
+  $seen = $x
+[$seen, $x]";
+  let expected: WoojinValue = WoojinValue::Array(vec![WoojinValue::String("inner".to_string()), WoojinValue::Int(1)]);
+  assert_eq!(eval(src).unwrap(), expected);
+}
+
+#[test]
+fn assignment_in_a_block_changes_the_outer_binding() {
+  assert_eq!(eval("let mut n = 1\nif uglyguri:\n  $n = 2\n$n").unwrap(), WoojinValue::Int(2));
+}
+
+#[test]
+fn loaded_code_can_be_run_more_than_once() {
+  let mut interpreter: Interpreter = Interpreter::new();
+  interpreter.load_str("fn twice($n):\n  return $n * 2\ntwice(21)").unwrap();
+  assert_eq!(interpreter.run().unwrap(), WoojinValue::Int(42));
+  assert_eq!(interpreter.run().unwrap(), WoojinValue::Int(42));
+
+  interpreter.eval_str("let mut count = 0").unwrap();
+  interpreter.load_str("$count = $count + 1").unwrap();
+  for expected in 1..=3 { assert_eq!(interpreter.run().unwrap(), WoojinValue::Int(expected)); }
+
+  interpreter.load_str("let once = 1").unwrap();
+  assert_eq!(interpreter.run().unwrap(), WoojinValue::Unit);
+  assert_eq!(interpreter.run().unwrap_err().kind, WoojinErrorKind::VariableAlreadyDeclared);
+}